@@ -0,0 +1,18 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_auth_backend_code(_backend_code: &str) {
+    /// INPUT: Takes in Rust webserver BACKEND_CODE based on the web FRAMEWORK given in the input, which follows its CONVENTIONS
+    /// FUNCTION: Adds user signup, login and logout to the BACKEND_CODE without removing any existing functionality
+    /// LOGIC: The following routes must be added exactly as described:
+    ///   POST /signup with JSON body { "username": string, "password": string }. Returns 200 when the user is created and 409 if the username exists
+    ///   POST /login with JSON body { "username": string, "password": string }. Returns 200 with JSON body { "token": string } or 401 if the credentials are wrong
+    ///   POST /logout with header "Authorization: Bearer <token>". Returns 200 and invalidates the token or 401 if the token is unknown
+    /// IMPORTANT: Passwords are never stored in plain text. Hash every password with a random per-user salt before storing it
+    /// IMPORTANT: Tokens are random, stored in the server state as active sessions, and removed on logout
+    /// IMPORTANT: The server binds to the host read from the HOST environment variable, falling back to 127.0.0.1, on the port read from the PORT environment variable, falling back to 8080
+    /// IMPORTANT: Does not use ANY libraries other than those listed in INSTALLED_DEPENDENCIES or ALLOWED_DEPENDENCIES
+    /// IMPORTANT: If the code is made of "=== FILE: path ===" blocks, prints every file back in the same format
+    /// OUTPUT: Print ONLY the code, nothing else. This function ONLY prints code.
+    println!(OUTPUT)
+}
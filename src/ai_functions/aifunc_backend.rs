@@ -6,9 +6,18 @@ pub fn print_backend_webserver_code(_project_description_and_template: &str) {
     /// IMPORTANT: The backend code is ONLY an example. If the Project Description requires it, make as many changes as you like.
     /// IMPORTANT: You do not need to follow the backend code exactly. Write functions that make sense for the users request if required.
     /// FUNCTION: Takes an existing set of code marked as CODE_TEMPLATE and updates or re-writes it to work for the purpose in the PROJECT_DESCRIPTION
-    /// IMPORTANT: The following libraries are already installed
-    ///   reqwest, serde, serde_json, tokio, actix-web, async-trait, actix_cors
+    /// IMPORTANT: The code is written for the web FRAMEWORK given in the input and follows its CONVENTIONS
+    /// IMPORTANT: The crates listed in INSTALLED_DEPENDENCIES are already installed
+    /// IMPORTANT: The crates listed in ALLOWED_DEPENDENCIES may also be used at the given versions. They are added to Cargo.toml automatically
     /// No other external libraries should be used. Write functions that fit with the description from the PROJECT_DESCRIPTION
+    /// IMPORTANT: If a DATA_MODEL and DATABASE_MODULE are provided, the module already exists at the given path. Declare it with "mod database;" in src/main.rs and use its structs and methods through "database::" for all data access. Do not copy it
+    /// IMPORTANT: Each URL in EXTERNAL_URLS is read at runtime from the environment variable named next to it, e.g. std::env::var("EXTERNAL_URL_1"), falling back to the URL itself
+    /// IMPORTANT: The server binds to the host read from the HOST environment variable, falling back to 127.0.0.1, on the port read from the PORT environment variable, falling back to 8080
+    /// IMPORTANT: If the project is large, split it into modules such as handlers, models, db and routes. Every file is printed in this format:
+    ///   === FILE: src/main.rs ===
+    ///   file contents
+    ///   === END FILE ===
+    /// Paths are relative to the project root. Only src/*.rs files are allowed, and src/main.rs declares every module
     /// OUTPUT: Print ONLY the code, nothing else. This function ONLY prints code.
     println!(OUTPUT)
 }
@@ -20,8 +29,11 @@ pub fn print_improved_webserver_code(_project_description_and_template: &str) {
     ///   1. Removes any bugs in the code and adds minor additional functionality
     ///   2. Makes sure everything requested in the spec from a backend standpoint was followed. If not, add the feature. No code should be implemented later. Everything should be written now.
     ///   3. ONLY writes the code. No commentary.
-    /// IMPORTANT: The following libraries are already installed. Does not use ANY libraries other than what was provided in the template
-    ///   reqwest, serde, serde_json, tokio, actix-web, async-trait
+    /// IMPORTANT: The server binds to the host read from the HOST environment variable, falling back to 127.0.0.1, on the port read from the PORT environment variable, falling back to 8080
+    /// IMPORTANT: The code is written for the web FRAMEWORK given in the input and follows its CONVENTIONS
+    /// IMPORTANT: Does not use ANY libraries other than those listed in INSTALLED_DEPENDENCIES or ALLOWED_DEPENDENCIES
+    /// IMPORTANT: Each URL in EXTERNAL_URLS is read at runtime from the environment variable named next to it, e.g. std::env::var("EXTERNAL_URL_1"), falling back to the URL itself
+    /// IMPORTANT: If the code is made of "=== FILE: path ===" blocks, prints every file back in the same format
     println!(OUTPUT)
 }
 
@@ -29,13 +41,51 @@ pub fn print_improved_webserver_code(_project_description_and_template: &str) {
 pub fn print_fixed_code(_broken_code_with_bugs: &str) {
     /// INPUT: Takes in Rust BROKEN_CODE and the ERROR_BUGS found
     /// FUNCTION: Removes bugs from code
+    /// IMPORTANT: The server binds to the host read from the HOST environment variable, falling back to 127.0.0.1, on the port read from the PORT environment variable, falling back to 8080
+    /// IMPORTANT: If the code is made of "=== FILE: path ===" blocks, prints every file back in the same format
     /// IMPORTANT: Only prints out the new and improved code. No commentary or anything else
     println!(OUTPUT)
 }
 
+#[ai_function]
+pub fn print_improved_code_edits(_project_description_and_code: &str) {
+    /// INPUT: Takes in a PROJECT_DESCRIPTION and the current BACKEND_CODE for a website backend build
+    /// FUNCTION: Performs the same improvements as a full rewrite, but prints them as edits to the BACKEND_CODE:
+    ///   1. Removes any bugs in the code and adds minor additional functionality
+    ///   2. Makes sure everything requested in the spec from a backend standpoint was followed. If not, add the feature
+    /// LOGIC: Every edit is printed as a block in exactly this format:
+    ///   <<<<<<< SEARCH
+    ///   lines copied exactly from BACKEND_CODE, including indentation
+    ///   =======
+    ///   the lines that replace them
+    ///   >>>>>>> REPLACE
+    /// IMPORTANT: SEARCH sections are short, unique in the code and copied character for character. Code that does not change is never printed
+    /// IMPORTANT: If the code is made of "=== FILE: path ===" blocks, each SEARCH section comes from a single file
+    /// IMPORTANT: Each URL in EXTERNAL_URLS is read at runtime from the environment variable named next to it, e.g. std::env::var("EXTERNAL_URL_1"), falling back to the URL itself
+    /// IMPORTANT: The server binds to the host read from the HOST environment variable, falling back to 127.0.0.1, on the port read from the PORT environment variable, falling back to 8080
+    /// IMPORTANT: Only prints the edit blocks. No commentary or anything else
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_fixed_code_edits(_broken_code_with_bugs: &str) {
+    /// INPUT: Takes in Rust BROKEN_CODE and the ERROR_BUGS found
+    /// FUNCTION: Removes bugs from code by printing edits to the BROKEN_CODE
+    /// LOGIC: Every edit is printed as a block in exactly this format:
+    ///   <<<<<<< SEARCH
+    ///   lines copied exactly from BROKEN_CODE, including indentation
+    ///   =======
+    ///   the lines that replace them
+    ///   >>>>>>> REPLACE
+    /// IMPORTANT: SEARCH sections are short, unique in the code and copied character for character. Code that does not change is never printed
+    /// IMPORTANT: If the code is made of "=== FILE: path ===" blocks, each SEARCH section comes from a single file
+    /// IMPORTANT: Only prints the edit blocks. No commentary or anything else
+    println!(OUTPUT)
+}
+
 #[ai_function]
 pub fn print_rest_api_endpoints(_code_input: &str) {
-    /// INPUT: Takes in Rust webserver CODE_INPUT based on actix-web
+    /// INPUT: Takes in Rust webserver CODE_INPUT based on the web FRAMEWORK given in the input
     /// FUNCTION: Prints out the JSON schema for url endpoints and their respective types
     /// LOGIC: Script analyses all code and can categorize into the following object keys:
     ///   "route": This represents the url path of the endpoint
@@ -43,6 +93,7 @@ pub fn print_rest_api_endpoints(_code_input: &str) {
     ///   "method": This represents the method being called
     ///   "request_body": This represents the body of a post method request
     ///   "response": This represents the output based upon the structs in the code and understanding the functions
+    /// IMPORTANT: Path parameters are always printed with curly braces, whatever the FRAMEWORK writes. An axum route "/item/:id" and an actix-web route "/item/{id}" are both printed as "/item/{id}"
     /// IMPORTANT: Only prints out the JSON schema. No commentary or anything else.
     /// MUST READ: All keys are strings. Even bool should be wrapped in double quotes as "bool"
     /// EXAMPLE:
@@ -59,6 +110,7 @@ pub fn print_rest_api_endpoints(_code_input: &str) {
     ///   pub password: String,
     /// }
     /// ...
+    /// With FRAMEWORK actix-web the routes are registered as:
     /// HttpServer::new(move || {
     ///   App::new()
     ///       .app_data(data.clone())
@@ -68,7 +120,14 @@ pub fn print_rest_api_endpoints(_code_input: &str) {
     ///       .route("/item/{id}", web::delete().to(delete_item))
     ///       .route("/signup", web::post().to(signup))
     ///       .route("/crypto", web::get().to(crypto))
-    /// PRINTS JSON FORMATTED OUTPUT:
+    /// With FRAMEWORK axum the same routes are registered as:
+    /// Router::new()
+    ///     .route("/item", post(create_item))
+    ///     .route("/item/:id", get(read_item).put(update_item).delete(delete_item))
+    ///     .route("/signup", post(signup))
+    ///     .route("/crypto", get(crypto))
+    ///     .with_state(state)
+    /// Both PRINT the same JSON FORMATTED OUTPUT:
     /// [
     ///   {
     ///     "route": "/item/{id}",
@@ -0,0 +1,48 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_data_model(_project_description: &str) {
+    /// Input: Takes in a project description of a website build that requires CRUD functionality
+    /// Function: Derives the entities that need to be stored in a database and the fields of each entity
+    /// Important: Every entity must have an "id" field of type "number"
+    /// Important: Field types can only be one of "number", "string", "bool", "float" or "list"
+    /// Output: Prints a list response of entities in the following format:
+    ///   [
+    ///     {
+    ///       "name": "EntityName",
+    ///       "fields": [{ "name": "field_name", "field_type": "number" }, ...]
+    ///     },
+    ///     ...
+    ///   ]
+    /// Example:
+    ///   project_description = "build a website that lets users manage a TODO list"
+    ///   prints:
+    ///   [
+    ///     {
+    ///       "name": "Task",
+    ///       "fields": [
+    ///         { "name": "id", "field_type": "number" },
+    ///         { "name": "title", "field_type": "string" },
+    ///         { "name": "completed", "field_type": "bool" }
+    ///       ]
+    ///     }
+    ///   ]
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_database_code(_data_model: &str) {
+    /// INPUT: Takes in a DATA_MODEL which is a JSON list of entities and their fields
+    /// IMPORTANT: If PREVIOUS_ATTEMPT_ERRORS are given, the previous module had these problems. Make sure they are fixed
+    /// FUNCTION: Writes a typed JSON file store module in Rust for the entities in the DATA_MODEL
+    /// LOGIC: The module contains:
+    ///   1. A struct for every entity deriving Serialize, Deserialize, Clone and Debug
+    ///   2. A Database struct holding a HashMap<u64, Entity> for every entity
+    ///   3. Database::load_from_file and Database::save_to_file reading and writing "database.json"
+    ///   4. insert, get, get_all, update and delete methods for every entity
+    /// IMPORTANT: The following libraries are already installed
+    ///   serde, serde_json, std
+    /// No other external libraries should be used.
+    /// OUTPUT: Print ONLY the code, nothing else. This function ONLY prints code.
+    println!(OUTPUT)
+}
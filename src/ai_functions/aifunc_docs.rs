@@ -0,0 +1,14 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_project_readme(_project_description_and_scope: &str) {
+    /// INPUT: Takes in a PROJECT_DESCRIPTION, PROJECT_SCOPE and API_ENDPOINT_SCHEMA of a generated Rust web backend
+    /// FUNCTION: Writes the opening of a README.md in markdown for the project
+    /// LOGIC: The README contains the following sections:
+    ///   1. A title and a short overview of what the web server does
+    ///   2. "## Features" listing what the server supports based on the PROJECT_SCOPE
+    ///   3. "## Running" explaining the server is started with `cargo run` and listens on http://localhost:8080
+    /// IMPORTANT: Does not write an endpoint table or example requests. Those are added afterwards
+    /// OUTPUT: Print ONLY the markdown, nothing else.
+    println!(OUTPUT)
+}
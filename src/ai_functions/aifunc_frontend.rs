@@ -0,0 +1,13 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_frontend_code(_project_description_and_api_schema: &str) {
+    /// INPUT: Takes in a PROJECT_DESCRIPTION, the API_ENDPOINT_SCHEMA of an already built website backend and the BACKEND_BASE_URL it is served from
+    /// FUNCTION: Writes a single static HTML page with inline JavaScript that acts as the frontend for the backend
+    /// IMPORTANT: Every route in the API_ENDPOINT_SCHEMA must be called using fetch on the BACKEND_BASE_URL given in the input, kept in one constant at the top of the script
+    /// IMPORTANT: Dynamic routes such as /item/{id} must have their parameters filled in from user input on the page
+    /// IMPORTANT: Requests with a request_body must send JSON built from form inputs matching the keys of the request_body
+    /// IMPORTANT: No external libraries, frameworks or CDN links. Only plain HTML, CSS and JavaScript in one file
+    /// OUTPUT: Print ONLY the code, nothing else. This function ONLY prints code.
+    println!(OUTPUT)
+}
@@ -0,0 +1,19 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_security_findings(_backend_code: &str) {
+    /// INPUT: Takes in Rust webserver BACKEND_CODE that was written by AI and has not been run yet
+    /// FUNCTION: Reviews the code for anything that could harm the machine it runs on or leak data
+    /// LOGIC: Looks for, but is not limited to:
+    ///   running external processes, deleting or overwriting files outside the project, listening on 0.0.0.0,
+    ///   hardcoded secrets or API keys, unsafe code, unbounded loops or allocations driven by user input
+    /// IMPORTANT: severity is one of "low", "medium", "high" or "critical"
+    /// IMPORTANT: If BACKEND_CODE is made of "=== FILE: path ===" blocks, file is the path of the block the finding is in and line is the number printed in that block. Otherwise file is null and line is the number printed in BACKEND_CODE. line is null if no single line applies
+    /// IMPORTANT: Prints an empty list [] if nothing risky is found
+    /// OUTPUT: Prints a list response of findings in the following format:
+    ///   [
+    ///     { "severity": "high", "rule": "file_deletion", "message": "Deletes database.json on every request", "file": "src/handlers.rs", "line": 42 },
+    ///     ...
+    ///   ]
+    println!(OUTPUT)
+}
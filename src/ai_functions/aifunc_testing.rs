@@ -0,0 +1,17 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_api_tests(_backend_code_and_api_schema: &str) {
+    /// INPUT: Takes in Rust webserver BACKEND_CODE based on the web FRAMEWORK given in the input and its API_ENDPOINT_SCHEMA
+    /// FUNCTION: Writes a Rust test module of integration tests for every route in the API_ENDPOINT_SCHEMA
+    /// LOGIC: The test module is saved next to main.rs and declared with #[cfg(test)] mod api_tests;
+    ///   1. Starts with use super::*; and follows the TEST_CONVENTIONS of the FRAMEWORK
+    ///   2. Each test builds the application with the same state and routes as main
+    ///   3. Sends requests using the route method, filling dynamic segments such as {id} with values created earlier in the same test
+    ///   4. Sends a JSON body built from the request_body keys and types when one is provided
+    ///   5. Asserts the status is a success and that every key of the response is present in the returned JSON
+    /// IMPORTANT: If PREVIOUS_TEST_FAILURES are given, the earlier tests failed with them. Tests that expected behaviour the BACKEND_CODE does not have are corrected
+    /// IMPORTANT: Does not use ANY libraries other than those listed in INSTALLED_DEPENDENCIES
+    /// OUTPUT: Print ONLY the code, nothing else. This function ONLY prints code.
+    println!(OUTPUT)
+}
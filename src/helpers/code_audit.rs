@@ -0,0 +1,308 @@
+use crate::helpers::file_tree::FileTree;
+use crate::models::agents::agent_traits::{AuditFinding, Severity};
+use dotenv::dotenv;
+use std::env;
+use std::str::FromStr;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+const SECRET_NAME_HINTS: [&str; 5] = ["secret", "password", "api_key", "apikey", "token"];
+const FILE_DELETION_FUNCTIONS: [&str; 3] = ["remove_file", "remove_dir", "remove_dir_all"];
+
+// Comma separated list such as "critical,high". Findings of these severities block running the code.
+pub fn blocking_severities() -> Vec<Severity> {
+    dotenv().ok();
+
+    match env::var("AUDIT_BLOCKING_SEVERITIES") {
+        Ok(severities) => severities
+            .split(',')
+            .filter_map(|severity| Severity::from_str(severity.trim()).ok())
+            .collect(),
+        Err(_) => vec![Severity::Critical],
+    }
+}
+
+pub fn is_blocking(findings: &[AuditFinding], blocking: &[Severity]) -> bool {
+    findings
+        .iter()
+        .any(|finding| blocking.contains(&finding.severity))
+}
+
+// Blocking findings are sent back to the fix step like build errors.
+pub fn blocking_findings_for_fix(findings: &[AuditFinding], blocking: &[Severity]) -> String {
+    findings
+        .iter()
+        .filter(|finding| blocking.contains(&finding.severity))
+        .map(|finding| {
+            let location: String = match (&finding.file, finding.line) {
+                (Some(file), Some(line)) => format!("{}:{}", file, line),
+                (Some(file), None) => file.clone(),
+                (None, Some(line)) => format!("line {}", line),
+                (None, None) => "unknown line".to_string(),
+            };
+
+            format!(
+                "[{}] {} ({}): {}",
+                finding.severity, finding.rule, location, finding.message
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+struct AuditVisitor {
+    findings: Vec<AuditFinding>,
+}
+
+impl AuditVisitor {
+    fn push(&mut self, severity: Severity, rule: &str, message: String, span: proc_macro2::Span) {
+        self.findings.push(AuditFinding {
+            severity,
+            rule: rule.to_string(),
+            message,
+            file: None,
+            line: Some(span.start().line),
+        });
+    }
+
+    fn check_secret(&mut self, name: &str, expr: &syn::Expr) {
+        let lowered: String = name.to_lowercase();
+
+        if !SECRET_NAME_HINTS.iter().any(|hint| lowered.contains(hint)) {
+            return;
+        }
+
+        if let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit_str),
+            ..
+        }) = expr
+        {
+            if !lit_str.value().is_empty() {
+                self.push(
+                    Severity::High,
+                    "hardcoded_secret",
+                    format!("`{}` is assigned a hardcoded string", name),
+                    lit_str.span(),
+                );
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for AuditVisitor {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        let segments: Vec<String> = path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+
+        if segments
+            .windows(2)
+            .any(|pair| pair == ["process", "Command"])
+            || segments == ["Command", "new"]
+        {
+            self.push(
+                Severity::Critical,
+                "process_command",
+                format!(
+                    "Spawns external processes through `{}`",
+                    segments.join("::")
+                ),
+                path.span(),
+            );
+        }
+
+        if let Some(last) = segments.last() {
+            if FILE_DELETION_FUNCTIONS.contains(&last.as_str()) {
+                self.push(
+                    Severity::High,
+                    "file_deletion",
+                    format!("Deletes files through `{}`", segments.join("::")),
+                    path.span(),
+                );
+            }
+        }
+
+        visit::visit_path(self, path);
+    }
+
+    fn visit_lit_str(&mut self, lit_str: &'ast syn::LitStr) {
+        if lit_str.value().contains("0.0.0.0") {
+            self.push(
+                Severity::Medium,
+                "public_listener",
+                "Listens on every network interface (0.0.0.0)".to_string(),
+                lit_str.span(),
+            );
+        }
+
+        visit::visit_lit_str(self, lit_str);
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let (syn::Pat::Ident(pat_ident), Some(init)) = (&local.pat, &local.init) {
+            self.check_secret(&pat_ident.ident.to_string(), &init.expr);
+        }
+
+        visit::visit_local(self, local);
+    }
+
+    fn visit_item_const(&mut self, item_const: &'ast syn::ItemConst) {
+        self.check_secret(&item_const.ident.to_string(), &item_const.expr);
+        visit::visit_item_const(self, item_const);
+    }
+
+    fn visit_item_static(&mut self, item_static: &'ast syn::ItemStatic) {
+        self.check_secret(&item_static.ident.to_string(), &item_static.expr);
+        visit::visit_item_static(self, item_static);
+    }
+
+    fn visit_expr_unsafe(&mut self, expr_unsafe: &'ast syn::ExprUnsafe) {
+        self.push(
+            Severity::Critical,
+            "unsafe",
+            "Uses an unsafe block".to_string(),
+            expr_unsafe.unsafe_token.span,
+        );
+        visit::visit_expr_unsafe(self, expr_unsafe);
+    }
+
+    fn visit_signature(&mut self, signature: &'ast syn::Signature) {
+        if let Some(unsafety) = &signature.unsafety {
+            self.push(
+                Severity::Critical,
+                "unsafe",
+                format!("Declares unsafe fn `{}`", signature.ident),
+                unsafety.span,
+            );
+        }
+        visit::visit_signature(self, signature);
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        if let Some(unsafety) = &item_impl.unsafety {
+            self.push(
+                Severity::Critical,
+                "unsafe",
+                "Declares an unsafe impl".to_string(),
+                unsafety.span,
+            );
+        }
+        visit::visit_item_impl(self, item_impl);
+    }
+}
+
+pub fn audit_backend_code(code: &str) -> Vec<AuditFinding> {
+    let syntax: syn::File = match syn::parse_file(code) {
+        Ok(syntax) => syntax,
+        Err(e) => {
+            return vec![AuditFinding {
+                severity: Severity::Low,
+                rule: "unparsed".to_string(),
+                message: format!("Code could not be parsed for auditing: {}", e),
+                file: None,
+                line: Some(e.span().start().line),
+            }]
+        }
+    };
+
+    let mut visitor: AuditVisitor = AuditVisitor { findings: vec![] };
+    visitor.visit_file(&syntax);
+    visitor.findings
+}
+
+// Audits each Rust file of a multi-file project on its own so line numbers stay meaningful.
+pub fn audit_generated_code(backend_code: &str) -> Vec<AuditFinding> {
+    let file_tree: FileTree = match FileTree::from_code(backend_code) {
+        Ok(file_tree) => file_tree,
+        Err(_) => return audit_backend_code(backend_code),
+    };
+
+    let rust_files: Vec<(&String, &String)> = file_tree
+        .files
+        .iter()
+        .filter(|(path, _)| path.ends_with(".rs"))
+        .collect();
+
+    if rust_files.len() == 1 {
+        return audit_backend_code(rust_files[0].1);
+    }
+
+    rust_files
+        .into_iter()
+        .flat_map(|(path, contents)| {
+            audit_backend_code(contents)
+                .into_iter()
+                .map(move |finding| AuditFinding {
+                    file: Some(path.clone()),
+                    ..finding
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_audit_backend_code() {
+        let code: &str = r#"
+use std::process::Command;
+
+const API_KEY: &str = "sk-12345";
+
+fn cleanup() {
+    std::fs::remove_file("database.json").unwrap();
+    let output = Command::new("ls").output();
+    unsafe { libc_call() };
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    HttpServer::new(|| App::new()).bind("0.0.0.0:8080")?.run().await
+}
+"#;
+
+        let findings: Vec<AuditFinding> = audit_backend_code(code);
+        let rules: Vec<&str> = findings.iter().map(|f| f.rule.as_str()).collect();
+
+        assert!(rules.contains(&"process_command"));
+        assert!(rules.contains(&"hardcoded_secret"));
+        assert!(rules.contains(&"file_deletion"));
+        assert!(rules.contains(&"unsafe"));
+        assert!(rules.contains(&"public_listener"));
+
+        let secret: &AuditFinding = findings
+            .iter()
+            .find(|f| f.rule == "hardcoded_secret")
+            .unwrap();
+        assert_eq!(secret.line, Some(4));
+    }
+
+    #[test]
+    fn tests_is_blocking() {
+        let findings: Vec<AuditFinding> = audit_backend_code("fn main() { unsafe {} }");
+
+        assert!(is_blocking(&findings, &[Severity::Critical]));
+        assert!(!is_blocking(&findings, &[Severity::Low]));
+
+        assert_eq!(
+            blocking_findings_for_fix(&findings, &[Severity::Critical]),
+            "[critical] unsafe (line 1): Uses an unsafe block"
+        );
+        assert_eq!(blocking_findings_for_fix(&findings, &[Severity::Low]), "");
+    }
+
+    #[test]
+    fn tests_audit_generated_code() {
+        let envelope: &str = "=== FILE: src/main.rs ===\nmod db;\nfn main() {}\n=== END FILE ===\n=== FILE: src/db.rs ===\npub fn reset() {\n    std::fs::remove_file(\"db.json\").unwrap();\n}\n=== END FILE ===\n";
+
+        let findings: Vec<AuditFinding> = audit_generated_code(envelope);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].file.as_deref(), Some("src/db.rs"));
+        assert_eq!(findings[0].line, Some(2));
+    }
+}
@@ -4,6 +4,11 @@ use crossterm::{
 };
 use std::io::{stdin, stdout};
 
+use super::code_audit::{blocking_severities, is_blocking};
+use super::general::active_run_workspace;
+use super::sandbox::selected_sandbox;
+use crate::models::agents::agent_traits::{AuditFinding, Severity};
+
 #[derive(PartialEq, Debug)]
 pub enum PrintCommand {
     AiCall,
@@ -27,6 +32,10 @@ impl PrintCommand {
         stdout.execute(SetForegroundColor(statement_color)).unwrap();
         println!("{}", agent_statement);
 
+        if let Some(run_workspace) = active_run_workspace() {
+            run_workspace.append_log(&format!("Agent: {}: {}", agent_pos, agent_statement));
+        }
+
         stdout.execute((ResetColor)).unwrap();
     }
 }
@@ -49,13 +58,60 @@ pub fn get_user_response(questions: &str) -> String {
     user_input.trim().to_string()
 }
 
-pub fn confirm_safe_code() -> bool {
+fn print_audit_findings(findings: &[AuditFinding]) {
     let mut stdout: std::io::Stdout = stdout();
 
+    println!();
+    println!("Code review findings:");
+
+    if findings.is_empty() {
+        println!("  No risky patterns found.");
+    }
+
+    for finding in findings {
+        let severity_color: Color = match finding.severity {
+            Severity::Low => Color::Grey,
+            Severity::Medium => Color::Yellow,
+            Severity::High => Color::Red,
+            Severity::Critical => Color::DarkRed,
+        };
+
+        let line: String = match (&finding.file, finding.line) {
+            (Some(file), Some(line)) => format!("{} line {}", file, line),
+            (Some(file), None) => file.clone(),
+            (None, Some(line)) => format!("line {}", line),
+            (None, None) => "unknown line".to_string(),
+        };
+
+        stdout.execute(SetForegroundColor(severity_color)).unwrap();
+        print!("  [{}] ", finding.severity);
+        stdout.execute(ResetColor).unwrap();
+        println!("{} ({}): {}", finding.rule, line, finding.message);
+    }
+}
+
+pub fn confirm_safe_code(findings: &[AuditFinding]) -> bool {
+    let mut stdout: std::io::Stdout = stdout();
+
+    print_audit_findings(findings);
+
+    let blocking: Vec<Severity> = blocking_severities();
+
+    if is_blocking(findings, &blocking) {
+        stdout.execute(SetForegroundColor(Color::DarkRed)).unwrap();
+        println!("Blocked: the code has findings with a blocking severity.");
+        stdout.execute(ResetColor).unwrap();
+        return false;
+    }
+
     loop {
         stdout.execute(SetForegroundColor(Color::Blue)).unwrap();
         println!("");
         println!("You are about to run code generated by AI.");
+        match selected_sandbox() {
+            Ok(sandbox) => println!("Sandbox: {}", sandbox.describe()),
+            Err(e) => println!("Sandbox: {}", e),
+        }
         println!("Confirm if you want to continue.");
         stdout.execute(ResetColor).unwrap();
 
@@ -0,0 +1,586 @@
+use crate::helpers::file_tree::FileTree;
+use crate::helpers::general::{project_root, read_project_file, save_project_file};
+use crate::helpers::sandbox::selected_sandbox;
+use crate::models::agents::agent_traits::{AuditFinding, Severity};
+use dotenv::dotenv;
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Crates the generated project may add on top of its template, each pinned to one version.
+// Entries are `name=version` with optional `+feature` suffixes.
+const DEFAULT_DEPENDENCY_ALLOWLIST: &str =
+    "uuid=1.6.1+v4+serde,chrono=0.4.31+serde,rand=0.8.5,jsonwebtoken=9.2.0,bcrypt=0.15.0,futures=0.3.30";
+
+// Roots that never need a manifest entry.
+const BUILTIN_ROOTS: [&str; 24] = [
+    "std",
+    "core",
+    "alloc",
+    "proc_macro",
+    "crate",
+    "self",
+    "super",
+    "Self",
+    "bool",
+    "char",
+    "str",
+    "f32",
+    "f64",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "usize",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllowedDependency {
+    pub name: String,
+    pub version: String,
+    pub features: Vec<String>,
+}
+
+impl AllowedDependency {
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (name, rest) = entry.trim().split_once('=')?;
+        let mut parts = rest.split('+').map(str::trim);
+        let version: &str = parts.next().filter(|version| !version.is_empty())?;
+
+        Some(Self {
+            name: name.trim().to_string(),
+            version: version.to_string(),
+            features: parts
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+
+    // The `=` requirement keeps cargo from resolving anything but the pinned version.
+    pub fn manifest_line(&self) -> String {
+        if self.features.is_empty() {
+            return format!("{} = \"={}\"", self.name, self.version);
+        }
+
+        let features: Vec<String> = self
+            .features
+            .iter()
+            .map(|feature| format!("\"{}\"", feature))
+            .collect();
+
+        format!(
+            "{} = {{ version = \"={}\", features = [{}] }}",
+            self.name,
+            self.version,
+            features.join(", ")
+        )
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{} {}", self.name, self.version)
+    }
+}
+
+pub fn dependency_allowlist() -> Vec<AllowedDependency> {
+    dotenv().ok();
+
+    let allowlist: String = env::var("DEPENDENCY_ALLOWLIST")
+        .unwrap_or_else(|_| DEFAULT_DEPENDENCY_ALLOWLIST.to_string());
+
+    allowlist
+        .split(',')
+        .filter_map(AllowedDependency::parse)
+        .collect()
+}
+
+// How the allowlist is shown to the LLM.
+pub fn allowed_dependencies_str(allowlist: &[AllowedDependency]) -> String {
+    allowlist
+        .iter()
+        .map(AllowedDependency::describe)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// With CARGO_NET_OFFLINE=true, or a sandbox without network, new dependencies must already be in
+// the local registry or vendored.
+pub fn offline_checks_enabled() -> bool {
+    dotenv().ok();
+
+    let offline: bool = env::var("CARGO_NET_OFFLINE")
+        .map(|offline| offline.trim().eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+
+    offline
+        || selected_sandbox()
+            .map(|sandbox| sandbox.isolates_network())
+            .unwrap_or(true)
+}
+
+// Crate names as they appear in code, where cargo's dashes become underscores.
+fn normalize(name: &str) -> String {
+    name.trim().replace('-', "_")
+}
+
+// Dev-dependencies count as installed, since the generated test modules use them.
+pub fn manifest_dependencies(cargo_toml: &str) -> BTreeSet<String> {
+    let mut dependencies: BTreeSet<String> = BTreeSet::new();
+    let mut in_dependencies: bool = false;
+
+    for line in cargo_toml.lines().map(str::trim) {
+        if line.starts_with('[') {
+            let header: &str = line.trim_matches(|c| c == '[' || c == ']');
+            in_dependencies = header == "dependencies" || header == "dev-dependencies";
+
+            if let Some(name) = header
+                .strip_prefix("dependencies.")
+                .or_else(|| header.strip_prefix("dev-dependencies."))
+            {
+                dependencies.insert(normalize(name));
+            }
+            continue;
+        }
+
+        if in_dependencies {
+            if let Some((name, _)) = line.split_once('=') {
+                dependencies.insert(normalize(name));
+            }
+        }
+    }
+
+    dependencies
+}
+
+#[derive(Default)]
+struct CrateVisitor {
+    use_roots: BTreeSet<String>,
+    path_roots: BTreeSet<String>,
+    imported: BTreeSet<String>,
+    modules: BTreeSet<String>,
+    // Items and generic parameters declared in the code, so `Task::new()` is not a crate.
+    local_items: BTreeSet<String>,
+}
+
+impl CrateVisitor {
+    fn visit_use_tree_names(&mut self, tree: &syn::UseTree) {
+        match tree {
+            syn::UseTree::Path(path) => self.visit_use_tree_names(&path.tree),
+            syn::UseTree::Name(name) => {
+                self.imported.insert(name.ident.to_string());
+            }
+            syn::UseTree::Rename(rename) => {
+                self.imported.insert(rename.rename.to_string());
+            }
+            syn::UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.visit_use_tree_names(tree);
+                }
+            }
+            syn::UseTree::Glob(_) => {}
+        }
+    }
+}
+
+fn use_tree_roots(tree: &syn::UseTree, roots: &mut BTreeSet<String>) {
+    match tree {
+        syn::UseTree::Path(path) => {
+            roots.insert(path.ident.to_string());
+        }
+        syn::UseTree::Name(name) => {
+            roots.insert(name.ident.to_string());
+        }
+        syn::UseTree::Rename(rename) => {
+            roots.insert(rename.ident.to_string());
+        }
+        syn::UseTree::Group(group) => {
+            for tree in &group.items {
+                use_tree_roots(tree, roots);
+            }
+        }
+        syn::UseTree::Glob(_) => {}
+    }
+}
+
+impl<'ast> Visit<'ast> for CrateVisitor {
+    fn visit_item_use(&mut self, item: &'ast syn::ItemUse) {
+        use_tree_roots(&item.tree, &mut self.use_roots);
+        self.visit_use_tree_names(&item.tree);
+    }
+
+    fn visit_item_extern_crate(&mut self, item: &'ast syn::ItemExternCrate) {
+        self.use_roots.insert(item.ident.to_string());
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        self.modules.insert(item.ident.to_string());
+        visit::visit_item_mod(self, item);
+    }
+
+    fn visit_item(&mut self, item: &'ast syn::Item) {
+        let ident: Option<&syn::Ident> = match item {
+            syn::Item::Struct(item) => Some(&item.ident),
+            syn::Item::Enum(item) => Some(&item.ident),
+            syn::Item::Type(item) => Some(&item.ident),
+            syn::Item::Trait(item) => Some(&item.ident),
+            syn::Item::Fn(item) => Some(&item.sig.ident),
+            syn::Item::Const(item) => Some(&item.ident),
+            syn::Item::Static(item) => Some(&item.ident),
+            syn::Item::Union(item) => Some(&item.ident),
+            _ => None,
+        };
+
+        if let Some(ident) = ident {
+            self.local_items.insert(ident.to_string());
+        }
+        visit::visit_item(self, item);
+    }
+
+    fn visit_generic_param(&mut self, param: &'ast syn::GenericParam) {
+        match param {
+            syn::GenericParam::Type(param) => {
+                self.local_items.insert(param.ident.to_string());
+            }
+            syn::GenericParam::Const(param) => {
+                self.local_items.insert(param.ident.to_string());
+            }
+            syn::GenericParam::Lifetime(_) => {}
+        }
+        visit::visit_generic_param(self, param);
+    }
+
+    // Single segment paths are locals or items in scope; only `a::b` can name a crate.
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if path.segments.len() > 1 {
+            if let Some(first) = path.segments.first() {
+                self.path_roots.insert(first.ident.to_string());
+            }
+        }
+        visit::visit_path(self, path);
+    }
+}
+
+// External crates the code refers to. Code that does not parse yields nothing and is left
+// for the build to report.
+pub fn used_crates(rust_sources: &str) -> BTreeSet<String> {
+    let file: syn::File = match syn::parse_file(rust_sources) {
+        Ok(file) => file,
+        Err(_) => return BTreeSet::new(),
+    };
+
+    let mut visitor: CrateVisitor = CrateVisitor::default();
+    visitor.visit_file(&file);
+
+    // `web::get()` after `use actix_web::web` goes through an import, not a crate.
+    let path_roots: BTreeSet<String> = visitor
+        .path_roots
+        .difference(&visitor.imported)
+        .cloned()
+        .collect();
+
+    visitor
+        .use_roots
+        .union(&path_roots)
+        .filter(|root| !BUILTIN_ROOTS.contains(&root.as_str()))
+        .filter(|root| !visitor.modules.contains(*root))
+        .filter(|root| !visitor.local_items.contains(*root))
+        .cloned()
+        .collect()
+}
+
+pub fn cargo_home() -> Option<PathBuf> {
+    env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cargo")))
+}
+
+pub fn is_available_offline(dependency: &AllowedDependency, project_root: &Path) -> bool {
+    if project_root.join("vendor").join(&dependency.name).is_dir() {
+        return true;
+    }
+
+    let archive: String = format!("{}-{}.crate", dependency.name, dependency.version);
+
+    let registries: fs::ReadDir = match cargo_home()
+        .map(|home| home.join("registry").join("cache"))
+        .and_then(|cache| fs::read_dir(cache).ok())
+    {
+        Some(registries) => registries,
+        None => return false,
+    };
+
+    registries
+        .filter_map(Result::ok)
+        .any(|registry| registry.path().join(&archive).is_file())
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DependencyPlan {
+    pub additions: Vec<AllowedDependency>,
+    pub rejected: Vec<String>,
+    pub unavailable: Vec<AllowedDependency>,
+}
+
+impl DependencyPlan {
+    pub fn is_approved(&self) -> bool {
+        self.rejected.is_empty() && self.unavailable.is_empty()
+    }
+
+    // New crates go through the same approval prompt as the audit findings.
+    pub fn audit_findings(&self) -> Vec<AuditFinding> {
+        let additions = self.additions.iter().map(|dependency| AuditFinding {
+            severity: Severity::Medium,
+            rule: "new_dependency".to_string(),
+            message: format!("Adds {} to Cargo.toml", dependency.describe()),
+            file: None,
+            line: None,
+        });
+
+        let rejected = self.rejected.iter().map(|name| AuditFinding {
+            severity: Severity::High,
+            rule: "unapproved_dependency".to_string(),
+            message: format!("Uses crate {} which is not on the allowlist", name),
+            file: None,
+            line: None,
+        });
+
+        let unavailable = self.unavailable.iter().map(|dependency| AuditFinding {
+            severity: Severity::High,
+            rule: "unavailable_dependency".to_string(),
+            message: format!(
+                "{} is not in the local registry or vendor directory",
+                dependency.describe()
+            ),
+            file: None,
+            line: None,
+        });
+
+        additions.chain(rejected).chain(unavailable).collect()
+    }
+
+    pub fn errors_for_fix(&self, allowlist: &[AllowedDependency]) -> String {
+        let mut errors: Vec<String> = vec![];
+
+        for name in &self.rejected {
+            errors.push(format!("crate `{}` is not allowed, remove it", name));
+        }
+
+        for dependency in &self.unavailable {
+            errors.push(format!(
+                "crate `{}` can't be downloaded offline, remove it",
+                dependency.name
+            ));
+        }
+
+        errors.push(format!(
+            "Only the template's crates and these may be used: {}",
+            allowed_dependencies_str(allowlist)
+        ));
+
+        errors.join("\n")
+    }
+}
+
+pub fn plan_dependencies(
+    backend_code: &str,
+    cargo_toml: &str,
+    allowlist: &[AllowedDependency],
+    offline_root: Option<&Path>,
+) -> DependencyPlan {
+    let rust_sources: String = FileTree::from_code(backend_code)
+        .map(|tree| tree.rust_sources())
+        .unwrap_or_else(|_| backend_code.to_string());
+
+    let installed: BTreeSet<String> = manifest_dependencies(cargo_toml);
+    let mut plan: DependencyPlan = DependencyPlan::default();
+
+    for name in used_crates(&rust_sources).difference(&installed) {
+        let allowed: Option<&AllowedDependency> = allowlist
+            .iter()
+            .find(|dependency| normalize(&dependency.name) == *name);
+
+        match allowed {
+            Some(dependency)
+                if offline_root.is_some_and(|root| !is_available_offline(dependency, root)) =>
+            {
+                plan.unavailable.push(dependency.clone())
+            }
+            Some(dependency) => plan.additions.push(dependency.clone()),
+            None => plan.rejected.push(name.clone()),
+        }
+    }
+
+    plan
+}
+
+// Appends the new lines at the end of the [dependencies] table, creating it when missing.
+pub fn add_dependencies(cargo_toml: &str, additions: &[AllowedDependency]) -> String {
+    if additions.is_empty() {
+        return cargo_toml.to_string();
+    }
+
+    let new_lines: Vec<String> = additions
+        .iter()
+        .map(AllowedDependency::manifest_line)
+        .collect();
+    let mut lines: Vec<String> = cargo_toml.lines().map(str::to_string).collect();
+
+    let section_start: Option<usize> = lines
+        .iter()
+        .position(|line| line.trim() == "[dependencies]");
+
+    match section_start {
+        Some(start) => {
+            let mut end: usize = lines[start + 1..]
+                .iter()
+                .position(|line| line.trim().starts_with('['))
+                .map(|offset| start + 1 + offset)
+                .unwrap_or(lines.len());
+
+            while end > start + 1 && lines[end - 1].trim().is_empty() {
+                end -= 1;
+            }
+
+            lines.splice(end..end, new_lines);
+        }
+        None => {
+            lines.push(String::new());
+            lines.push("[dependencies]".to_string());
+            lines.extend(new_lines);
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+// The plan for the generated project, checked against its current Cargo.toml.
+pub fn project_dependency_plan(backend_code: &str) -> DependencyPlan {
+    let offline_root: Option<PathBuf> = offline_checks_enabled().then(project_root);
+
+    plan_dependencies(
+        backend_code,
+        &read_project_file("Cargo.toml"),
+        &dependency_allowlist(),
+        offline_root.as_deref(),
+    )
+}
+
+// Adds approved crates to the project's Cargo.toml, or returns the errors to send back to the LLM.
+pub fn update_project_dependencies(backend_code: &str) -> Result<Vec<AllowedDependency>, String> {
+    let plan: DependencyPlan = project_dependency_plan(backend_code);
+
+    if !plan.is_approved() {
+        return Err(plan.errors_for_fix(&dependency_allowlist()));
+    }
+
+    if !plan.additions.is_empty() {
+        let cargo_toml: String =
+            add_dependencies(&read_project_file("Cargo.toml"), &plan.additions);
+        save_project_file("Cargo.toml", &cargo_toml);
+    }
+
+    Ok(plan.additions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::templates::BUILTIN_TEMPLATES;
+
+    const CARGO_TOML: &str = "[package]\nname = \"actix_template\"\nversion = \"0.1.0\"\n\n[dependencies]\nactix-web = \"4.3.1\"\nserde = { version = \"1.0\", features = [\"derive\"] }\nserde_json = \"1.0\"\n\n[dev-dependencies]\nreqwest = \"0.11\"\n";
+
+    const CODE: &str = "use actix_web::{web, App, HttpResponse};\nuse serde::Serialize;\nuse db::load;\nmod db;\n\nstruct Database;\nenum Status { Done }\n\nfn first<T: Default>() -> T {\n    T::default()\n}\n\n#[actix_web::main]\nasync fn main() {\n    let db = Database::load_from_file();\n    let status = Status::Done;\n    let id = uuid::Uuid::new_v4();\n    let body = serde_json::json!({ \"id\": id.to_string() });\n    let limit = u64::MAX;\n    db::load();\n    web::get();\n    let token = jsonwebtoken::encode();\n    let hash = md5::compute(\"x\");\n}\n";
+
+    fn allowlist() -> Vec<AllowedDependency> {
+        DEFAULT_DEPENDENCY_ALLOWLIST
+            .split(',')
+            .filter_map(AllowedDependency::parse)
+            .collect()
+    }
+
+    #[test]
+    fn tests_used_crates() {
+        let used: Vec<String> = used_crates(CODE).into_iter().collect();
+
+        assert_eq!(
+            used,
+            vec![
+                "actix_web",
+                "jsonwebtoken",
+                "md5",
+                "serde",
+                "serde_json",
+                "uuid"
+            ]
+        );
+    }
+
+    #[test]
+    fn tests_builtin_templates_need_no_new_dependencies() {
+        for template in &BUILTIN_TEMPLATES {
+            let file = |path: &str| -> &str {
+                template
+                    .files
+                    .iter()
+                    .find(|(file_path, _)| *file_path == path)
+                    .map(|(_, contents)| *contents)
+                    .unwrap()
+            };
+
+            let plan: DependencyPlan =
+                plan_dependencies(file("src/main.rs"), file("Cargo.toml"), &allowlist(), None);
+
+            assert!(
+                plan.rejected.is_empty(),
+                "{}: {:?}",
+                template.name,
+                plan.rejected
+            );
+            assert!(plan.additions.is_empty(), "{}", template.name);
+        }
+    }
+
+    #[test]
+    fn tests_plan_dependencies() {
+        let plan: DependencyPlan = plan_dependencies(CODE, CARGO_TOML, &allowlist(), None);
+
+        let added: Vec<&str> = plan.additions.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(added, vec!["jsonwebtoken", "uuid"]);
+        assert_eq!(plan.rejected, vec!["md5".to_string()]);
+        assert!(!plan.is_approved());
+        assert_eq!(plan.audit_findings().len(), 3);
+
+        let root: PathBuf = env::temp_dir().join(format!("dependencies_{}", std::process::id()));
+        fs::create_dir_all(root.join("vendor").join("uuid")).unwrap();
+
+        let offline: DependencyPlan =
+            plan_dependencies(CODE, CARGO_TOML, &allowlist(), Some(&root));
+        assert!(offline.additions.iter().any(|d| d.name == "uuid"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn tests_add_dependencies() {
+        let uuid: AllowedDependency = AllowedDependency::parse("uuid=1.6.1+v4+serde").unwrap();
+        let rand: AllowedDependency = AllowedDependency::parse("rand=0.8.5").unwrap();
+
+        let updated: String = add_dependencies(CARGO_TOML, &[uuid, rand]);
+
+        assert!(updated.contains(
+            "serde_json = \"1.0\"\nuuid = { version = \"=1.6.1\", features = [\"v4\", \"serde\"] }\nrand = \"=0.8.5\"\n\n[dev-dependencies]"
+        ));
+
+        let installed: BTreeSet<String> = manifest_dependencies(&updated);
+        assert!(installed.contains("uuid") && installed.contains("actix_web"));
+        assert!(installed.contains("reqwest"));
+    }
+}
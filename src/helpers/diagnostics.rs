@@ -0,0 +1,532 @@
+use crate::helpers::file_tree::FileTree;
+use crate::helpers::general::{
+    build_backend_project, clippy_backend_project, fmt_check_backend_project, record_backend_build,
+    save_backend_code,
+};
+use dotenv::dotenv;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::process::Output;
+
+// Path of the generated backend code as cargo reports it, relative to the project root.
+pub const MAIN_SOURCE_FILE: &str = "src/main.rs";
+// Path of the data access module written by the database designer.
+pub const DATABASE_SOURCE_FILE: &str = "src/database.rs";
+
+const SOURCE_CONTEXT_LINES: usize = 2;
+const MAX_AUTO_FIX_ROUNDS: usize = 3;
+const MAX_FMT_DIFF_LINES: usize = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub file: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub replacement: String,
+    pub is_machine_applicable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompilerDiagnostic {
+    pub level: String,
+    pub code: Option<String>,
+    pub message: String,
+    pub file: Option<String>,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl CompilerDiagnostic {
+    pub fn is_error(&self) -> bool {
+        self.level == "error"
+    }
+}
+
+// Mirrors the parts of rustc's JSON diagnostic format that are used here.
+#[derive(Debug, Deserialize)]
+struct RawSpan {
+    file_name: String,
+    byte_start: usize,
+    byte_end: usize,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    is_primary: bool,
+    suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCode {
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawDiagnostic {
+    message: String,
+    code: Option<RawCode>,
+    level: String,
+    spans: Vec<RawSpan>,
+    children: Vec<RawDiagnostic>,
+}
+
+fn collect_suggestions(diagnostic: &RawDiagnostic, suggestions: &mut Vec<Suggestion>) {
+    for span in &diagnostic.spans {
+        if let Some(replacement) = &span.suggested_replacement {
+            suggestions.push(Suggestion {
+                file: span.file_name.clone(),
+                byte_start: span.byte_start,
+                byte_end: span.byte_end,
+                replacement: replacement.clone(),
+                is_machine_applicable: span.suggestion_applicability.as_deref()
+                    == Some("MachineApplicable"),
+            });
+        }
+    }
+
+    for child in &diagnostic.children {
+        collect_suggestions(child, suggestions);
+    }
+}
+
+impl From<RawDiagnostic> for CompilerDiagnostic {
+    fn from(raw: RawDiagnostic) -> Self {
+        let mut suggestions: Vec<Suggestion> = vec![];
+        collect_suggestions(&raw, &mut suggestions);
+
+        let primary: Option<&RawSpan> = raw
+            .spans
+            .iter()
+            .find(|span| span.is_primary)
+            .or(raw.spans.first());
+
+        Self {
+            level: raw.level,
+            code: raw.code.map(|code| code.code),
+            message: raw.message,
+            file: primary.map(|span| span.file_name.clone()),
+            line_start: primary.map(|span| span.line_start).unwrap_or_default(),
+            line_end: primary.map(|span| span.line_end).unwrap_or_default(),
+            column_start: primary.map(|span| span.column_start).unwrap_or_default(),
+            suggestions,
+        }
+    }
+}
+
+// Reads `cargo build --message-format=json` stdout. Lines that are not compiler messages are skipped.
+pub fn parse_cargo_messages(stdout: &str) -> Vec<CompilerDiagnostic> {
+    stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-message")
+        .filter_map(|message| {
+            serde_json::from_value::<RawDiagnostic>(message["message"].clone()).ok()
+        })
+        .filter(|diagnostic| diagnostic.level != "failure-note")
+        .map(CompilerDiagnostic::from)
+        .collect()
+}
+
+// Applies machine-applicable suggestions from errors, last first so earlier byte offsets stay valid.
+// Overlapping suggestions are skipped. Returns the new source and how many were applied.
+pub fn apply_machine_applicable(
+    source: &str,
+    diagnostics: &[CompilerDiagnostic],
+    file: &str,
+) -> (String, usize) {
+    let mut suggestions: Vec<&Suggestion> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        .flat_map(|diagnostic| &diagnostic.suggestions)
+        .filter(|suggestion| suggestion.is_machine_applicable && suggestion.file == file)
+        .collect();
+
+    suggestions.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.byte_start));
+
+    let mut fixed: String = source.to_string();
+    let mut applied: usize = 0;
+    let mut lowest_start: usize = usize::MAX;
+
+    for suggestion in suggestions {
+        let in_bounds: bool = suggestion.byte_start <= suggestion.byte_end
+            && suggestion.byte_end <= fixed.len()
+            && fixed.is_char_boundary(suggestion.byte_start)
+            && fixed.is_char_boundary(suggestion.byte_end);
+
+        if !in_bounds || suggestion.byte_end > lowest_start {
+            continue;
+        }
+
+        fixed.replace_range(
+            suggestion.byte_start..suggestion.byte_end,
+            &suggestion.replacement,
+        );
+        lowest_start = suggestion.byte_start;
+        applied += 1;
+    }
+
+    (fixed, applied)
+}
+
+fn source_context(source: &str, line_start: usize, line_end: usize) -> String {
+    let first: usize = line_start.saturating_sub(SOURCE_CONTEXT_LINES).max(1);
+    let last: usize = line_end + SOURCE_CONTEXT_LINES;
+
+    source
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(number, _)| (first..=last).contains(number))
+        .map(|(number, line)| format!("{:>5} | {}", number, line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Only errors are sent, each with the source lines around it, instead of the full cargo output.
+pub fn format_errors_for_fix(diagnostics: &[CompilerDiagnostic], file_tree: &FileTree) -> String {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        .map(|diagnostic| {
+            let code: String = diagnostic
+                .code
+                .as_ref()
+                .map(|code| format!("[{}]", code))
+                .unwrap_or_default();
+
+            let location: String = match &diagnostic.file {
+                Some(file) => format!(
+                    "{}:{}:{}",
+                    file, diagnostic.line_start, diagnostic.column_start
+                ),
+                None => "unknown location".to_string(),
+            };
+
+            let context: String = diagnostic
+                .file
+                .as_ref()
+                .and_then(|file| file_tree.files.get(file))
+                .map(|source| source_context(source, diagnostic.line_start, diagnostic.line_end))
+                .unwrap_or_default();
+
+            format!(
+                "error{}: {}\n  --> {}\n{}",
+                code, diagnostic.message, location, context
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+#[derive(Debug)]
+pub struct BuildReport {
+    pub success: bool,
+    pub diagnostics: Vec<CompilerDiagnostic>,
+    pub auto_fixes: usize,
+    pub stderr: String,
+    pub backend_code: String,
+}
+
+impl BuildReport {
+    // Falls back to the raw stderr when cargo failed before reporting any compiler errors.
+    pub fn errors_for_fix(&self) -> String {
+        let errors: String =
+            format_errors_for_fix(&self.diagnostics, &code_file_tree(&self.backend_code));
+
+        if errors.is_empty() {
+            self.stderr.clone()
+        } else {
+            errors
+        }
+    }
+}
+
+fn code_file_tree(backend_code: &str) -> FileTree {
+    FileTree::from_code(backend_code).unwrap_or_else(|_| FileTree::single(backend_code))
+}
+
+// Builds the generated project, applying rustc's machine-applicable fixes and rebuilding
+// until it compiles or no more fixes apply. The fixed code is saved back to the project.
+pub fn build_with_auto_fixes(backend_code: &str) -> BuildReport {
+    let mut file_tree: FileTree = code_file_tree(backend_code);
+    let mut auto_fixes: usize = 0;
+
+    for round in 1..=MAX_AUTO_FIX_ROUNDS {
+        let output: Output = build_backend_project();
+        let diagnostics: Vec<CompilerDiagnostic> =
+            parse_cargo_messages(&String::from_utf8_lossy(&output.stdout));
+
+        let mut applied: usize = 0;
+
+        if !output.status.success() && round < MAX_AUTO_FIX_ROUNDS {
+            for (path, source) in file_tree.files.iter_mut() {
+                let (fixed_source, fixes) = apply_machine_applicable(source, &diagnostics, path);
+                *source = fixed_source;
+                applied += fixes;
+            }
+        }
+
+        if applied == 0 {
+            record_backend_build(output.status.success());
+
+            return BuildReport {
+                success: output.status.success(),
+                diagnostics,
+                auto_fixes,
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                backend_code: file_tree.to_code(),
+            };
+        }
+
+        save_backend_code(&file_tree.to_code(), "Compiler", "machine applicable fixes");
+        auto_fixes += applied;
+    }
+
+    unreachable!("the last round never applies fixes")
+}
+
+// Comma separated clippy lint groups such as "correctness,perf". Lints in these groups fail the build.
+pub fn clippy_deny_groups() -> Vec<String> {
+    dotenv().ok();
+
+    match env::var("CLIPPY_DENY_GROUPS") {
+        Ok(groups) => groups
+            .split(',')
+            .map(|group| group.trim().trim_start_matches("clippy::").to_lowercase())
+            .filter(|group| !group.is_empty())
+            .collect(),
+        Err(_) => vec!["correctness".to_string(), "suspicious".to_string()],
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct QualityReport {
+    pub fmt_diff: Option<String>,
+    pub lint_errors: Vec<CompilerDiagnostic>,
+    pub warnings: Vec<CompilerDiagnostic>,
+}
+
+impl QualityReport {
+    pub fn from_outputs(fmt_check: &Output, clippy: &Output) -> Self {
+        let fmt_diff: Option<String> = (!fmt_check.status.success()).then(|| {
+            String::from_utf8_lossy(&fmt_check.stdout)
+                .lines()
+                .take(MAX_FMT_DIFF_LINES)
+                .collect::<Vec<&str>>()
+                .join("\n")
+        });
+
+        let (lint_errors, warnings) =
+            parse_cargo_messages(&String::from_utf8_lossy(&clippy.stdout))
+                .into_iter()
+                .partition(CompilerDiagnostic::is_error);
+
+        Self {
+            fmt_diff,
+            lint_errors,
+            warnings: warnings
+                .into_iter()
+                .filter(|diagnostic| diagnostic.level == "warning")
+                .collect(),
+        }
+    }
+
+    pub fn is_passing(&self) -> bool {
+        self.fmt_diff.is_none() && self.lint_errors.is_empty()
+    }
+
+    pub fn errors_for_fix(&self, backend_code: &str) -> String {
+        let mut errors: Vec<String> = vec![];
+
+        if let Some(fmt_diff) = &self.fmt_diff {
+            errors.push(format!(
+                "The code is not formatted as rustfmt expects:\n{}",
+                fmt_diff
+            ));
+        }
+
+        if !self.lint_errors.is_empty() {
+            errors.push(format_errors_for_fix(
+                &self.lint_errors,
+                &code_file_tree(backend_code),
+            ));
+        }
+
+        errors.join("\n\n")
+    }
+
+    // One line per lint, most frequent first, e.g. "3 x clippy::needless_return".
+    pub fn warning_summary(&self) -> Vec<String> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+
+        for warning in &self.warnings {
+            let lint: String = warning
+                .code
+                .clone()
+                .unwrap_or_else(|| warning.message.clone());
+            *counts.entry(lint).or_default() += 1;
+        }
+
+        let mut summary: Vec<(String, usize)> = counts.into_iter().collect();
+        summary.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        summary
+            .into_iter()
+            .map(|(lint, count)| format!("{} x {}", count, lint))
+            .collect()
+    }
+}
+
+pub fn run_quality_gate() -> QualityReport {
+    let fmt_check: Output = fmt_check_backend_project();
+    let clippy: Output = clippy_backend_project(&clippy_deny_groups());
+
+    QualityReport::from_outputs(&fmt_check, &clippy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "use std::collections::HashMap;\n\nfn main() {\n    let total: u32 = 5;\n    let items = vec![1, 2];\n    println!(\"{}\", itemz.len());\n}\n";
+
+    fn cargo_output() -> String {
+        let unresolved: Value = serde_json::json!({
+            "reason": "compiler-message",
+            "message": {
+                "message": "cannot find value `itemz` in this scope",
+                "code": { "code": "E0425", "explanation": null },
+                "level": "error",
+                "spans": [{
+                    "file_name": "src/main.rs",
+                    "byte_start": 115,
+                    "byte_end": 120,
+                    "line_start": 6,
+                    "line_end": 6,
+                    "column_start": 20,
+                    "column_end": 25,
+                    "is_primary": true,
+                    "suggested_replacement": null,
+                    "suggestion_applicability": null
+                }],
+                "children": [{
+                    "message": "a local variable with a similar name exists",
+                    "code": null,
+                    "level": "help",
+                    "spans": [{
+                        "file_name": "src/main.rs",
+                        "byte_start": 115,
+                        "byte_end": 120,
+                        "line_start": 6,
+                        "line_end": 6,
+                        "column_start": 20,
+                        "column_end": 25,
+                        "is_primary": true,
+                        "suggested_replacement": "items",
+                        "suggestion_applicability": "MaybeIncorrect"
+                    }],
+                    "children": []
+                }]
+            }
+        });
+
+        let mismatched: Value = serde_json::json!({
+            "reason": "compiler-message",
+            "message": {
+                "message": "mismatched types",
+                "code": { "code": "E0308", "explanation": null },
+                "level": "error",
+                "spans": [{
+                    "file_name": "src/main.rs",
+                    "byte_start": 59,
+                    "byte_end": 62,
+                    "line_start": 4,
+                    "line_end": 4,
+                    "column_start": 16,
+                    "column_end": 19,
+                    "is_primary": true,
+                    "suggested_replacement": "u64",
+                    "suggestion_applicability": "MachineApplicable"
+                }],
+                "children": []
+            }
+        });
+
+        format!(
+            "{}\n{}\n{}\n",
+            r#"{"reason":"compiler-artifact","package_id":"web_template"}"#, unresolved, mismatched
+        )
+    }
+
+    #[test]
+    fn tests_parse_cargo_messages() {
+        let diagnostics: Vec<CompilerDiagnostic> = parse_cargo_messages(&cargo_output());
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("E0425"));
+        assert_eq!(diagnostics[0].line_start, 6);
+        assert_eq!(diagnostics[0].suggestions[0].replacement, "items");
+        assert!(!diagnostics[0].suggestions[0].is_machine_applicable);
+        assert!(diagnostics[1].suggestions[0].is_machine_applicable);
+    }
+
+    #[test]
+    fn tests_apply_and_format() {
+        let diagnostics: Vec<CompilerDiagnostic> = parse_cargo_messages(&cargo_output());
+        let (fixed, applied) = apply_machine_applicable(SOURCE, &diagnostics, MAIN_SOURCE_FILE);
+
+        assert_eq!(applied, 1);
+        assert!(fixed.contains("let total: u64 = 5;"));
+        assert!(fixed.contains("itemz.len()"));
+
+        let report: String = format_errors_for_fix(&diagnostics[..1], &FileTree::single(SOURCE));
+        assert!(report.starts_with(
+            "error[E0425]: cannot find value `itemz` in this scope\n  --> src/main.rs:6:20\n"
+        ));
+        assert!(report.contains("    4 |     let total: u32 = 5;"));
+        assert!(report.contains("    6 |     println!(\"{}\", itemz.len());"));
+        assert!(!report.contains("    3 |"));
+    }
+
+    #[test]
+    fn tests_quality_report() {
+        fn lint(level: &str, code: &str) -> CompilerDiagnostic {
+            CompilerDiagnostic {
+                level: level.to_string(),
+                code: Some(code.to_string()),
+                message: code.to_string(),
+                file: Some(MAIN_SOURCE_FILE.to_string()),
+                line_start: 4,
+                line_end: 4,
+                column_start: 5,
+                suggestions: vec![],
+            }
+        }
+
+        let report: QualityReport = QualityReport {
+            fmt_diff: Some("Diff in src/main.rs at line 1:".to_string()),
+            lint_errors: vec![lint("error", "clippy::absurd_extreme_comparisons")],
+            warnings: vec![
+                lint("warning", "clippy::needless_return"),
+                lint("warning", "unused_variables"),
+                lint("warning", "clippy::needless_return"),
+            ],
+        };
+
+        assert!(!report.is_passing());
+        assert_eq!(
+            report.warning_summary(),
+            vec!["2 x clippy::needless_return", "1 x unused_variables"]
+        );
+
+        let errors: String = report.errors_for_fix(SOURCE);
+        assert!(errors.starts_with("The code is not formatted as rustfmt expects:"));
+        assert!(errors.contains("error[clippy::absurd_extreme_comparisons]"));
+
+        assert!(QualityReport::default().is_passing());
+    }
+}
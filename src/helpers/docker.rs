@@ -0,0 +1,316 @@
+use crate::helpers::general::WEB_SERVER_PORT;
+use dotenv::dotenv;
+use serde_yaml::Value;
+use std::env;
+
+const DOCKERFILE_INSTRUCTIONS: [&str; 18] = [
+    "FROM",
+    "RUN",
+    "CMD",
+    "LABEL",
+    "EXPOSE",
+    "ENV",
+    "ADD",
+    "COPY",
+    "ENTRYPOINT",
+    "VOLUME",
+    "USER",
+    "WORKDIR",
+    "ARG",
+    "ONBUILD",
+    "STOPSIGNAL",
+    "HEALTHCHECK",
+    "SHELL",
+    "MAINTAINER",
+];
+
+// Reads `name` from the [package] table without pulling in a toml parser.
+pub fn package_name(cargo_toml: &str) -> Option<String> {
+    let mut in_package: bool = false;
+
+    for line in cargo_toml.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+
+        if in_package {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "name" {
+                    return Some(value.trim().trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// CONTAINER_PORT is the port the service listens on inside its container and publishes.
+pub fn container_port() -> u16 {
+    dotenv().ok();
+
+    env::var("CONTAINER_PORT")
+        .ok()
+        .and_then(|port| port.trim().parse().ok())
+        .unwrap_or(WEB_SERVER_PORT)
+}
+
+// BACKEND_BASE_URL is where the deployed backend is reached. Without it, the backend is expected
+// on localhost at the container port.
+pub fn backend_base_url() -> String {
+    dotenv().ok();
+
+    env::var("BACKEND_BASE_URL")
+        .map(|base_url| base_url.trim().trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| format!("http://localhost:{}", container_port()))
+}
+
+pub fn external_url_env_vars(external_urls: &[String]) -> Vec<(String, String)> {
+    external_urls
+        .iter()
+        .enumerate()
+        .map(|(index, url)| (format!("EXTERNAL_URL_{}", index + 1), url.clone()))
+        .collect()
+}
+
+// Tells the backend prompts which variable each external URL is read from.
+pub fn external_urls_context(external_urls: &[String]) -> String {
+    let env_vars: Vec<String> = external_url_env_vars(external_urls)
+        .into_iter()
+        .map(|(name, url)| format!("{}={}", name, url))
+        .collect();
+
+    format!("EXTERNAL_URLS: {} \n", env_vars.join(", "))
+}
+
+// Env vars the compose file sets but the backend code never reads.
+pub fn unread_env_vars(backend_code: &str, env_vars: &[(String, String)]) -> Vec<String> {
+    env_vars
+        .iter()
+        .filter(|(name, _)| !backend_code.contains(&format!("\"{}\"", name)))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+pub fn generate_dockerfile(binary_name: &str, port: u16) -> String {
+    format!(
+        "FROM rust:1-slim AS builder
+WORKDIR /app
+COPY Cargo.toml Cargo.lock* ./
+COPY src ./src
+RUN cargo build --release
+
+FROM debian:bookworm-slim AS runtime
+RUN apt-get update && apt-get install -y --no-install-recommends ca-certificates && rm -rf /var/lib/apt/lists/*
+WORKDIR /app
+COPY --from=builder /app/target/release/{binary_name} /usr/local/bin/{binary_name}
+ENV HOST=0.0.0.0
+ENV PORT={port}
+EXPOSE {port}
+CMD [\"{binary_name}\"]
+"
+    )
+}
+
+pub fn generate_dockerignore() -> String {
+    "target/\n.git/\n.env\n*.log\n".to_string()
+}
+
+pub fn generate_compose(service_name: &str, port: u16, env_vars: &[(String, String)]) -> String {
+    let mut compose: String = format!(
+        "services:
+  {service_name}:
+    build: .
+    ports:
+      - \"{port}:{port}\"
+    environment:
+      HOST: \"0.0.0.0\"
+      PORT: \"{port}\"
+"
+    );
+
+    for (name, value) in env_vars {
+        compose.push_str(&format!("      {}: \"{}\"\n", name, value));
+    }
+
+    compose
+}
+
+// Static checks only: every instruction is known, FROM comes first and COPY --from names a stage.
+pub fn lint_dockerfile(dockerfile: &str, port: u16) -> Vec<String> {
+    let mut issues: Vec<String> = vec![];
+    let mut stages: Vec<String> = vec![];
+    let mut exposes_port: bool = false;
+    let mut listens_everywhere: bool = false;
+    let mut continued: bool = false;
+
+    for (index, raw_line) in dockerfile.lines().enumerate() {
+        let line: &str = raw_line.trim();
+        let is_continuation: bool = continued;
+        continued = line.ends_with('\\');
+
+        if line.is_empty() || line.starts_with('#') || is_continuation {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let instruction: String = words.next().unwrap_or_default().to_uppercase();
+        let arguments: Vec<&str> = words.collect();
+
+        if !DOCKERFILE_INSTRUCTIONS.contains(&instruction.as_str()) {
+            issues.push(format!(
+                "line {}: unknown instruction {}",
+                index + 1,
+                instruction
+            ));
+            continue;
+        }
+
+        if stages.is_empty() && instruction != "FROM" && instruction != "ARG" {
+            issues.push(format!("line {}: {} before FROM", index + 1, instruction));
+        }
+
+        if arguments.is_empty() {
+            issues.push(format!(
+                "line {}: {} has no arguments",
+                index + 1,
+                instruction
+            ));
+            continue;
+        }
+
+        match instruction.as_str() {
+            "FROM" => {
+                let stage: String = match arguments.as_slice() {
+                    [_, alias_kw, alias] if alias_kw.eq_ignore_ascii_case("as") => {
+                        alias.to_string()
+                    }
+                    _ => format!("{}", stages.len()),
+                };
+                stages.push(stage);
+            }
+            "COPY" => {
+                for argument in &arguments {
+                    if let Some(stage) = argument.strip_prefix("--from=") {
+                        if !stages.iter().any(|name| name == stage) {
+                            issues.push(format!("line {}: unknown stage {}", index + 1, stage));
+                        }
+                    }
+                }
+            }
+            "ENV" => {
+                listens_everywhere |= arguments.contains(&"HOST=0.0.0.0");
+            }
+            "EXPOSE" => {
+                exposes_port |= arguments
+                    .iter()
+                    .any(|argument| argument.split('/').next() == Some(&port.to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    if stages.len() < 2 {
+        issues.push("Dockerfile is not a multi-stage build".to_string());
+    }
+
+    if !exposes_port {
+        issues.push(format!("Dockerfile does not expose port {}", port));
+    }
+
+    // The servers bind to $HOST, and 127.0.0.1 is unreachable from outside the container.
+    if !listens_everywhere {
+        issues.push("Dockerfile does not set HOST=0.0.0.0".to_string());
+    }
+
+    issues
+}
+
+pub fn lint_compose(compose: &str, port: u16) -> Vec<String> {
+    let document: Value = match serde_yaml::from_str(compose) {
+        Ok(document) => document,
+        Err(e) => return vec![format!("Compose file is not valid YAML: {}", e)],
+    };
+
+    let services = match document.get("services").and_then(Value::as_mapping) {
+        Some(services) if !services.is_empty() => services,
+        _ => return vec!["Compose file has no services".to_string()],
+    };
+
+    let mut issues: Vec<String> = vec![];
+    let port_mapping: String = format!("{}:{}", port, port);
+
+    for (name, service) in services {
+        let name: &str = name.as_str().unwrap_or("<unnamed>");
+
+        if service.get("build").is_none() && service.get("image").is_none() {
+            issues.push(format!("Service {} has neither build nor image", name));
+        }
+
+        let publishes_port: bool = service
+            .get("ports")
+            .and_then(Value::as_sequence)
+            .map(|ports| {
+                ports
+                    .iter()
+                    .any(|p| p.as_str() == Some(port_mapping.as_str()))
+            })
+            .unwrap_or(false);
+
+        if !publishes_port {
+            issues.push(format!(
+                "Service {} does not publish {}",
+                name, port_mapping
+            ));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_generated_files_pass_lint() {
+        let env_vars: Vec<(String, String)> =
+            external_url_env_vars(&["https://api.binance.com/api/v3/exchangeInfo".to_string()]);
+
+        let dockerfile: String = generate_dockerfile("web_template", 8080);
+        let compose: String = generate_compose("web_template", 8080, &env_vars);
+
+        assert_eq!(lint_dockerfile(&dockerfile, 8080), Vec::<String>::new());
+        assert_eq!(lint_compose(&compose, 8080), Vec::<String>::new());
+        assert!(compose.contains("EXTERNAL_URL_1: \"https://api.binance.com/api/v3/exchangeInfo\""));
+        assert!(compose.contains("HOST: \"0.0.0.0\""));
+
+        assert_eq!(
+            unread_env_vars("let url = env::var(\"EXTERNAL_URL_1\");", &env_vars),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            unread_env_vars("let url = \"https://api.binance.com\";", &env_vars),
+            vec!["EXTERNAL_URL_1".to_string()]
+        );
+    }
+
+    #[test]
+    fn tests_lint_dockerfile_issues() {
+        let dockerfile: &str = "COPY . .\nFROM rust AS builder\nCOPY --from=build /a /b\nRUNN x\n";
+        let issues: Vec<String> = lint_dockerfile(dockerfile, 8080);
+
+        assert!(issues.contains(&"line 1: COPY before FROM".to_string()));
+        assert!(issues.contains(&"line 3: unknown stage build".to_string()));
+        assert!(issues.contains(&"line 4: unknown instruction RUNN".to_string()));
+        assert!(issues.contains(&"Dockerfile is not a multi-stage build".to_string()));
+        assert!(issues.contains(&"Dockerfile does not set HOST=0.0.0.0".to_string()));
+    }
+
+    #[test]
+    fn tests_package_name() {
+        let cargo_toml: &str = "[package]\nname = \"web_template\"\nversion = \"0.1.0\"\n\n[dependencies]\nname = \"x\"\n";
+        assert_eq!(package_name(cargo_toml), Some("web_template".to_string()));
+    }
+}
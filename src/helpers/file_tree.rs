@@ -0,0 +1,292 @@
+use crate::helpers::diagnostics::MAIN_SOURCE_FILE;
+use crate::helpers::templates::CODE_TEMPLATE_FILE;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const FILE_START_PREFIX: &str = "=== FILE: ";
+const FILE_START_SUFFIX: &str = " ===";
+const FILE_END_MARKER: &str = "=== END FILE ===";
+const STAGING_SUFFIX: &str = ".auto_gpt_staging";
+
+// The generated project as a set of files keyed by their path relative to the project root.
+// A project that is only src/main.rs round-trips as plain code, anything larger as an envelope:
+//   === FILE: src/main.rs ===
+//   ...
+//   === END FILE ===
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FileTree {
+    pub files: BTreeMap<String, String>,
+}
+
+// Only Rust sources under src/ may be written by the LLM. Cargo.toml is managed through the
+// dependency allowlist.
+pub fn validate_path(path: &str) -> Result<(), String> {
+    let relative: &Path = Path::new(path);
+
+    let is_contained: bool = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+
+    let is_allowed: bool = path.starts_with("src/") && path.ends_with(".rs");
+
+    if path.is_empty() || !is_contained || !is_allowed {
+        return Err(format!("File path {} is not allowed", path));
+    }
+
+    Ok(())
+}
+
+impl FileTree {
+    pub fn single(code: &str) -> Self {
+        let mut files: BTreeMap<String, String> = BTreeMap::new();
+        files.insert(MAIN_SOURCE_FILE.to_string(), code.to_string());
+        Self { files }
+    }
+
+    pub fn parse_envelope(text: &str) -> Result<Self, String> {
+        let mut files: BTreeMap<String, String> = BTreeMap::new();
+        let mut lines = text.lines();
+
+        while let Some(line) = lines.next() {
+            let path: &str = match line
+                .trim()
+                .strip_prefix(FILE_START_PREFIX)
+                .and_then(|rest| rest.strip_suffix(FILE_START_SUFFIX))
+            {
+                Some(path) => path.trim(),
+                None => continue,
+            };
+
+            validate_path(path)?;
+
+            let mut contents: Vec<&str> = vec![];
+            let mut closed: bool = false;
+
+            for line in lines.by_ref() {
+                if line.trim() == FILE_END_MARKER {
+                    closed = true;
+                    break;
+                }
+                contents.push(line);
+            }
+
+            if !closed {
+                return Err(format!(
+                    "File {} is not closed with {}",
+                    path, FILE_END_MARKER
+                ));
+            }
+
+            if files
+                .insert(path.to_string(), contents.join("\n") + "\n")
+                .is_some()
+            {
+                return Err(format!("File {} appears more than once", path));
+            }
+        }
+
+        if !files.contains_key(MAIN_SOURCE_FILE) {
+            return Err(format!("The project has no {}", MAIN_SOURCE_FILE));
+        }
+
+        Ok(Self { files })
+    }
+
+    // Plain code without any FILE markers is treated as the whole of src/main.rs.
+    pub fn from_code(code: &str) -> Result<Self, String> {
+        let is_envelope: bool = code
+            .lines()
+            .any(|line| line.trim().starts_with(FILE_START_PREFIX));
+
+        if is_envelope {
+            Self::parse_envelope(code)
+        } else {
+            Ok(Self::single(code))
+        }
+    }
+
+    pub fn to_code(&self) -> String {
+        if self.files.len() == 1 {
+            if let Some(main) = self.files.get(MAIN_SOURCE_FILE) {
+                return main.clone();
+            }
+        }
+
+        self.files
+            .iter()
+            .map(|(path, contents)| {
+                format!(
+                    "{}{}{}\n{}\n{}\n",
+                    FILE_START_PREFIX,
+                    path,
+                    FILE_START_SUFFIX,
+                    contents.trim_end_matches('\n'),
+                    FILE_END_MARKER
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // Every Rust file in one string, for static analysis that only needs the items.
+    pub fn rust_sources(&self) -> String {
+        self.files
+            .iter()
+            .filter(|(path, _)| path.ends_with(".rs"))
+            .map(|(_, contents)| contents.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+
+    // Reads the Rust sources of a project from disk. The code template is not part of the code.
+    pub fn read_sources(root: &Path) -> std::io::Result<Self> {
+        let mut files: BTreeMap<String, String> = BTreeMap::new();
+
+        for (relative, path) in source_files(root)? {
+            if relative != CODE_TEMPLATE_FILE {
+                files.insert(relative, fs::read_to_string(&path)?);
+            }
+        }
+
+        Ok(Self { files })
+    }
+
+    // Writes every file to a staging copy first and only renames them into place once all
+    // writes succeeded, so a failure never leaves the project half updated.
+    pub fn write_atomically(&self, root: &Path) -> std::io::Result<()> {
+        let mut staged: Vec<(PathBuf, PathBuf)> = vec![];
+
+        for (path, contents) in &self.files {
+            let target: PathBuf = root.join(path);
+            let staging: PathBuf = root.join(format!("{}{}", path, STAGING_SUFFIX));
+
+            let written: std::io::Result<()> = target
+                .parent()
+                .map(fs::create_dir_all)
+                .unwrap_or(Ok(()))
+                .and_then(|_| fs::write(&staging, contents));
+
+            if let Err(e) = written {
+                for (staging, _) in staged {
+                    let _ = fs::remove_file(staging);
+                }
+                return Err(e);
+            }
+
+            staged.push((staging, target));
+        }
+
+        for (staging, target) in staged {
+            fs::rename(staging, target)?;
+        }
+
+        // The tree is the whole backend, so a module it dropped must not stay around to be
+        // compiled or picked up by the route extractor.
+        for (relative, path) in source_files(root)? {
+            if !self.files.contains_key(&relative) && relative != CODE_TEMPLATE_FILE {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Every Rust file under src/, keyed by its path relative to the project root.
+fn source_files(root: &Path) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut files: Vec<(String, PathBuf)> = vec![];
+    let mut directories: Vec<PathBuf> = vec![root.join("src")];
+
+    while let Some(directory) = directories.pop() {
+        if !directory.is_dir() {
+            continue;
+        }
+
+        for entry in fs::read_dir(&directory)? {
+            let path: PathBuf = entry?.path();
+
+            if path.is_dir() {
+                directories.push(path);
+                continue;
+            }
+
+            let relative: String = path
+                .strip_prefix(root)
+                .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+
+            if relative.ends_with(".rs") {
+                files.push((relative, path));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENVELOPE: &str = "=== FILE: src/main.rs ===\nmod models;\n\nfn main() {}\n=== END FILE ===\n\n=== FILE: src/models.rs ===\npub struct Item {\n    pub id: u64,\n}\n=== END FILE ===\n";
+
+    #[test]
+    fn tests_envelope_round_trip() {
+        let tree: FileTree = FileTree::from_code(ENVELOPE).unwrap();
+
+        assert_eq!(tree.files.len(), 2);
+        assert_eq!(
+            tree.files["src/models.rs"],
+            "pub struct Item {\n    pub id: u64,\n}\n"
+        );
+        assert_eq!(FileTree::from_code(&tree.to_code()).unwrap(), tree);
+        assert!(tree.rust_sources().contains("mod models;"));
+
+        let single: FileTree = FileTree::from_code("fn main() {}\n").unwrap();
+        assert_eq!(single.to_code(), "fn main() {}\n");
+    }
+
+    #[test]
+    fn tests_rejected_envelopes() {
+        assert!(validate_path("src/handlers/item.rs").is_ok());
+        assert!(validate_path("Cargo.toml").is_err());
+        assert!(validate_path("../src/main.rs").is_err());
+        assert!(validate_path("/etc/passwd").is_err());
+        assert!(validate_path("build.rs").is_err());
+
+        let unclosed: &str = "=== FILE: src/main.rs ===\nfn main() {}\n";
+        assert!(FileTree::parse_envelope(unclosed).is_err());
+
+        let no_main: &str = "=== FILE: src/models.rs ===\n=== END FILE ===\n";
+        assert!(FileTree::parse_envelope(no_main).is_err());
+    }
+
+    #[test]
+    fn tests_write_atomically() {
+        let root: PathBuf = std::env::temp_dir().join(format!("file_tree_{}", std::process::id()));
+        let tree: FileTree = FileTree::from_code(ENVELOPE).unwrap();
+
+        tree.write_atomically(&root).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("src/models.rs")).unwrap(),
+            tree.files["src/models.rs"]
+        );
+        assert!(!root.join("src/main.rs.auto_gpt_staging").exists());
+
+        fs::write(root.join(CODE_TEMPLATE_FILE), "// template\n").unwrap();
+        FileTree::single("fn main() {}\n")
+            .write_atomically(&root)
+            .unwrap();
+
+        assert!(!root.join("src/models.rs").exists());
+        assert!(root.join(CODE_TEMPLATE_FILE).exists());
+        assert_eq!(
+            fs::read_to_string(root.join("src/main.rs")).unwrap(),
+            "fn main() {}\n"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,206 @@
+use crate::helpers::dependencies::{
+    allowed_dependencies_str, dependency_allowlist, manifest_dependencies,
+};
+use crate::helpers::general::read_project_file;
+use crate::helpers::route_extractor::{extract_routes, ExtractedRoutes};
+use crate::helpers::server::rewrite_bind_calls;
+use dotenv::dotenv;
+use std::env;
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+
+// The web framework generated projects are written for. Everything that differs between
+// frameworks goes through here, so agents never branch on the framework themselves.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, EnumString)]
+pub enum Framework {
+    #[strum(to_string = "actix-web", serialize = "actix")]
+    ActixWeb,
+    #[strum(to_string = "axum")]
+    Axum,
+}
+
+impl Framework {
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            Self::ActixWeb => "actix-json-db",
+            Self::Axum => "axum-minimal",
+        }
+    }
+
+    pub fn code_conventions(&self) -> &'static str {
+        match self {
+            Self::ActixWeb => "actix-web 4. Routes are registered on App with .route(\"/item/{id}\", web::get().to(handler)). Handlers take extractors such as web::Data, web::Path and web::Json and return impl Responder. The server is started with HttpServer::new(...).bind((host, port))?.run().await inside #[actix_web::main], where host and port come from the HOST and PORT environment variables",
+            Self::Axum => "axum 0.7. Routes are registered on Router::new() with .route(\"/item/:id\", get(handler).delete(other_handler)) and shared state is added with .with_state(state). Handlers take extractors such as State, Path and Json and return Json<T>, StatusCode or impl IntoResponse. The server is started with axum::serve(tokio::net::TcpListener::bind((host, port)).await.unwrap(), app) inside #[tokio::main], where host and port come from the HOST and PORT environment variables",
+        }
+    }
+
+    pub fn test_conventions(&self) -> &'static str {
+        match self {
+            Self::ActixWeb => "Uses actix_web::{test, web, App}. Each test is an #[actix_web::test] async fn that builds the App with test::init_service using the same app_data and routes as main and sends requests with test::TestRequest",
+            Self::Axum => "Uses axum::body::Body, axum::http::{Request, StatusCode} and tower::ServiceExt. Each test is a #[tokio::test] async fn that builds the same Router as main and sends requests with router.oneshot(request). Response bodies are read with axum::body::to_bytes(response.into_body(), usize::MAX)",
+        }
+    }
+
+    // The call whose "host:port" argument is rewritten to read the PORT env var.
+    fn bind_call(&self) -> &'static str {
+        match self {
+            Self::ActixWeb => ".bind(",
+            Self::Axum => "TcpListener::bind(",
+        }
+    }
+
+    pub fn apply_port_convention(&self, code: &str) -> String {
+        rewrite_bind_calls(code, self.bind_call())
+    }
+
+    // Routes are always stored with {param} segments, whatever the framework writes.
+    pub fn normalize_route(&self, route: &str) -> String {
+        match self {
+            Self::ActixWeb => route.to_string(),
+            Self::Axum => route
+                .split('/')
+                .map(|segment| match segment.strip_prefix([':', '*']) {
+                    Some(param) if !param.is_empty() => format!("{{{}}}", param),
+                    _ => segment.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join("/"),
+        }
+    }
+
+    pub fn extract_routes(&self, code: &str) -> Result<ExtractedRoutes, syn::Error> {
+        let mut extracted: ExtractedRoutes = extract_routes(code)?;
+
+        for route_object in &mut extracted.routes {
+            route_object.route = self.normalize_route(&route_object.route);
+            route_object.is_route_dynamic = route_object.route.contains('{');
+        }
+
+        for (route, _) in &mut extracted.unresolved {
+            *route = self.normalize_route(route);
+        }
+
+        Ok(extracted)
+    }
+}
+
+// TARGET_FRAMEWORK is "actix-web" (the default) or "axum". Any other value is an error, so a
+// typo never silently builds an actix project.
+pub fn selected_framework() -> Result<Framework, String> {
+    dotenv().ok();
+
+    match env::var("TARGET_FRAMEWORK") {
+        Ok(framework) => Framework::from_str(framework.trim()).map_err(|_| {
+            format!(
+                "Unknown TARGET_FRAMEWORK value {:?}. Use {} or {}",
+                framework,
+                Framework::ActixWeb,
+                Framework::Axum
+            )
+        }),
+        Err(_) => Ok(Framework::ActixWeb),
+    }
+}
+
+// The framework is checked when the run starts, so agents can rely on it being valid.
+pub fn target_framework() -> Framework {
+    selected_framework().expect("Invalid framework configuration.")
+}
+
+// What every code generating prompt needs to know about the project it writes for.
+pub fn project_context() -> String {
+    let framework: Framework = target_framework();
+
+    let installed: Vec<String> = manifest_dependencies(&read_project_file("Cargo.toml"))
+        .into_iter()
+        .collect();
+
+    format!(
+        "FRAMEWORK: {} \n CONVENTIONS: {} \n INSTALLED_DEPENDENCIES: {} \n ALLOWED_DEPENDENCIES: {} \n",
+        framework,
+        framework.code_conventions(),
+        installed.join(", "),
+        allowed_dependencies_str(&dependency_allowlist())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agents::agent_traits::HttpMethod;
+
+    const AXUM_CODE: &str = r#"
+use axum::{extract::{Path, State}, routing::{get, post}, Json, Router};
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Task {
+    id: u64,
+    name: String,
+}
+
+async fn create_task(State(state): State<AppState>, Json(task): Json<Task>) -> Json<Task> {
+    Json(task)
+}
+
+async fn read_task(State(state): State<AppState>, Path(id): Path<u64>) -> impl IntoResponse {
+    let task: Task = state.get(id);
+    (StatusCode::OK, Json(task))
+}
+
+async fn delete_task(Path(id): Path<u64>) -> StatusCode {
+    StatusCode::OK
+}
+
+#[tokio::main]
+async fn main() {
+    let app = Router::new()
+        .route("/task", post(create_task))
+        .route("/task/:id", get(read_task).delete(delete_task))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+    axum::serve(listener, app).await.unwrap();
+}
+"#;
+
+    #[test]
+    fn tests_axum_routes() {
+        let extracted: ExtractedRoutes = Framework::Axum.extract_routes(AXUM_CODE).unwrap();
+
+        let routes: Vec<(&str, HttpMethod)> = extracted
+            .routes
+            .iter()
+            .map(|route_object| (route_object.route.as_str(), route_object.method))
+            .collect();
+
+        assert_eq!(
+            routes,
+            vec![
+                ("/task", HttpMethod::Post),
+                ("/task/{id}", HttpMethod::Get),
+                ("/task/{id}", HttpMethod::Delete),
+            ]
+        );
+        assert!(extracted.routes[1].is_route_dynamic);
+        assert!(extracted.routes[0].request_body.is_some());
+        assert!(extracted.routes[1].response.is_some());
+        assert_eq!(extracted.routes[1].response, extracted.routes[0].response);
+        assert!(extracted.unresolved.is_empty());
+    }
+
+    #[test]
+    fn tests_axum_port_convention() {
+        let converted: String = Framework::Axum.apply_port_convention(AXUM_CODE);
+
+        assert!(converted.contains(
+            r#"TcpListener::bind((std::env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()), std::env::var("PORT")"#
+        ));
+        assert!(converted.contains("unwrap_or(3000)))"));
+        assert_eq!(
+            Framework::Axum.normalize_route("/files/*path"),
+            "/files/{path}"
+        );
+        assert_eq!(Framework::from_str("actix"), Ok(Framework::ActixWeb));
+        assert_eq!(Framework::Axum.to_string(), "axum");
+    }
+}
@@ -0,0 +1,295 @@
+use crate::helpers::route_tester::{fill_path, sample_value, to_reqwest_method};
+use crate::helpers::server::ServerHandle;
+use crate::models::agents::agent_traits::{FieldSchema, HttpMethod, RouteObject};
+use dotenv::dotenv;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::env;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const ODD_PATH_SEGMENTS: [&str; 8] = [
+    "-1",
+    "0",
+    "99999999999999999999",
+    "%00",
+    "..",
+    "%F0%9F%98%80",
+    "'%20OR%201=1",
+    "null",
+];
+
+const OVERSIZED_PAYLOAD_BYTES: usize = 1024 * 1024;
+// How long a server that dropped a connection gets to exit before it is taken to be alive.
+const EXIT_CHECK_DELAY: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FuzzIssue {
+    ServerError(u16),
+    Hang,
+    Crash,
+    Panic(String),
+    // The request failed but the server is still running, e.g. a connection reset on an
+    // oversized payload.
+    TransportError(String),
+}
+
+impl FuzzIssue {
+    pub fn is_bug(&self) -> bool {
+        !matches!(self, Self::TransportError(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzFinding {
+    pub route: String,
+    pub method: HttpMethod,
+    pub input: String,
+    pub issue: FuzzIssue,
+}
+
+impl FuzzFinding {
+    pub fn describe(&self) -> String {
+        let issue: String = match &self.issue {
+            FuzzIssue::ServerError(status) => format!("returned status {}", status),
+            FuzzIssue::Hang => "did not respond in time".to_string(),
+            FuzzIssue::Crash => "crashed the server".to_string(),
+            FuzzIssue::Panic(line) => format!("panicked: {}", line),
+            FuzzIssue::TransportError(error) => format!("failed to send: {}", error),
+        };
+
+        let input: String = self.input.chars().take(120).collect();
+
+        format!(
+            "{} {} {} (input: {})",
+            self.method.to_string().to_uppercase(),
+            self.route,
+            issue,
+            input
+        )
+    }
+}
+
+// Seconds to spend fuzzing after the endpoint tests pass. Unset or 0 skips the stage.
+pub fn fuzz_duration() -> Option<Duration> {
+    dotenv().ok();
+
+    env::var("FUZZ_DURATION_SECS")
+        .ok()
+        .and_then(|seconds| seconds.trim().parse::<u64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .map(Duration::from_secs)
+}
+
+// xorshift64: enough randomness for picking inputs without another dependency.
+pub struct FuzzRng(u64);
+
+impl FuzzRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn from_time() -> Self {
+        let nanos: u64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or(1);
+
+        Self::new(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound.max(1) as u64) as usize
+    }
+}
+
+fn random_value(rng: &mut FuzzRng) -> Value {
+    match rng.below(8) {
+        0 => Value::Null,
+        1 => json!(-1),
+        2 => json!(i64::MAX),
+        3 => json!(f64::MIN_POSITIVE),
+        4 => json!(""),
+        5 => json!("\u{0}\u{feff}😀"),
+        6 => json!([]),
+        _ => json!({}),
+    }
+}
+
+// Keeps the expected keys but swaps values for the wrong type, or drops keys altogether.
+fn mutate_value(value: &Value, rng: &mut FuzzRng) -> Value {
+    match value {
+        Value::Object(fields) => {
+            let mut mutated: serde_json::Map<String, Value> = serde_json::Map::new();
+
+            for (name, field) in fields {
+                if rng.below(4) != 0 {
+                    mutated.insert(name.clone(), mutate_value(field, rng));
+                }
+            }
+
+            Value::Object(mutated)
+        }
+        _ => random_value(rng),
+    }
+}
+
+pub fn fuzz_body(schema: Option<&FieldSchema>, rng: &mut FuzzRng) -> String {
+    let valid: Value = schema.map(sample_value).unwrap_or(Value::Null);
+
+    match rng.below(6) {
+        0 => mutate_value(&valid, rng).to_string(),
+        1 => random_value(rng).to_string(),
+        2 => "{\"unterminated\": ".to_string(),
+        3 => format!("{{\"data\": \"{}\"}}", "a".repeat(OVERSIZED_PAYLOAD_BYTES)),
+        4 => format!("{}{}", "[".repeat(512), "]".repeat(512)),
+        _ => String::new(),
+    }
+}
+
+pub fn fuzz_path(route: &str, rng: &mut FuzzRng) -> String {
+    let segment: &str = ODD_PATH_SEGMENTS[rng.below(ODD_PATH_SEGMENTS.len())];
+
+    route
+        .split('/')
+        .map(|part| {
+            if part.starts_with('{') && part.ends_with('}') {
+                segment
+            } else {
+                part
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join("/")
+}
+
+pub fn scan_panics(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("panicked at"))
+        .map(|line| line.trim().to_string())
+        .collect()
+}
+
+async fn has_exited(server: &mut ServerHandle) -> bool {
+    if server.exit_status().is_some() {
+        return true;
+    }
+
+    tokio::time::sleep(EXIT_CHECK_DELAY).await;
+    server.exit_status().is_some()
+}
+
+// Rotates through every route until the deadline. Stops early once the server exits.
+pub async fn fuzz_routes(
+    client: &Client,
+    base_url: &str,
+    routes: &[RouteObject],
+    duration: Duration,
+    server: &mut ServerHandle,
+) -> Vec<FuzzFinding> {
+    let mut rng: FuzzRng = FuzzRng::from_time();
+    let mut findings: Vec<FuzzFinding> = vec![];
+    let deadline: Instant = Instant::now() + duration;
+
+    if routes.is_empty() {
+        return findings;
+    }
+
+    let mut index: usize = 0;
+
+    while Instant::now() < deadline {
+        let route_object: &RouteObject = &routes[index % routes.len()];
+        index += 1;
+
+        let path: String = if route_object.is_route_dynamic && rng.below(2) == 0 {
+            fuzz_path(&route_object.route, &mut rng)
+        } else {
+            fill_path(&route_object.route, None)
+        };
+
+        let body: String = fuzz_body(route_object.request_body.as_ref(), &mut rng);
+        let input: String = format!("{} {}", path, body);
+
+        let request = client
+            .request(
+                to_reqwest_method(route_object.method),
+                format!("{}{}", base_url, path),
+            )
+            .header("Content-Type", "application/json")
+            .body(body);
+
+        let issue: Option<FuzzIssue> = match request.send().await {
+            Ok(response) if response.status().is_server_error() => {
+                Some(FuzzIssue::ServerError(response.status().as_u16()))
+            }
+            Ok(_) => None,
+            Err(e) if e.is_timeout() => Some(FuzzIssue::Hang),
+            Err(_) if has_exited(server).await => Some(FuzzIssue::Crash),
+            Err(e) => Some(FuzzIssue::TransportError(e.to_string())),
+        };
+
+        if let Some(issue) = issue {
+            let is_fatal: bool = server.exit_status().is_some();
+
+            findings.push(FuzzFinding {
+                route: route_object.route.clone(),
+                method: route_object.method,
+                input,
+                issue,
+            });
+
+            if is_fatal {
+                break;
+            }
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_fuzz_inputs() {
+        let mut rng: FuzzRng = FuzzRng::new(7);
+
+        for _ in 0..50 {
+            let path: String = fuzz_path("/item/{id}/tag/{tag}", &mut rng);
+            assert!(path.starts_with("/item/") && !path.contains('{'));
+        }
+
+        let schema: FieldSchema =
+            FieldSchema::from_value(&json!({ "id": "number", "name": "string" })).unwrap();
+        let bodies: Vec<String> = (0..50)
+            .map(|_| fuzz_body(Some(&schema), &mut rng))
+            .collect();
+
+        assert!(bodies
+            .iter()
+            .any(|body| body.len() > OVERSIZED_PAYLOAD_BYTES));
+        assert!(bodies
+            .iter()
+            .any(|body| serde_json::from_str::<Value>(body).is_err()));
+    }
+
+    #[test]
+    fn tests_scan_panics() {
+        let stderr: &str = "    Finished dev [unoptimized] target(s)\n\
+            thread 'actix-rt|system:0|arbiter:0' panicked at src/main.rs:42:31:\n\
+            called `Option::unwrap()` on a `None` value\n";
+
+        assert_eq!(
+            scan_panics(stderr),
+            vec!["thread 'actix-rt|system:0|arbiter:0' panicked at src/main.rs:42:31:".to_string()]
+        );
+    }
+}
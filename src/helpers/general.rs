@@ -1,14 +1,65 @@
 use super::command_line::PrintCommand;
+use super::diagnostics::{DATABASE_SOURCE_FILE, MAIN_SOURCE_FILE};
+use super::file_tree::FileTree;
+use super::project_history::{
+    commit_iteration, has_history, record_build_result, roll_back_to_last_build,
+};
+use super::sandbox::{selected_sandbox, Sandbox};
+use super::server::ServerHandle;
+use super::templates::CODE_TEMPLATE_FILE;
+use super::workspace::{start_run, RunWorkspace};
 use crate::{api::call_request::call_gpt, models::general::llm::Message};
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::Mutex;
 
-pub const CODE_TEMPlATE_PATH: &str = "/home/migara/Desktop/projects/actix_template/src/template.rs";
-pub const EXEC_MAIN_PATH: &str = "/home/migara/Desktop/projects/actix_template/src/main.rs";
-pub const API_SCHEMA_PATH: &str =
-    "/home/migara/Desktop/projects/auto_gpt_init/schemas/api_schema.json";
-pub const WEB_SERVER_PROJECT_PATH: &str = "/home/migara/Desktop/projects/actix_template/";
+pub const API_TESTS_FILE: &str = "src/api_tests.rs";
+pub const WEB_SERVER_PORT: u16 = 8080;
+pub const README_FILE: &str = "README.md";
+pub const OPENAPI_FILE: &str = "openapi.yaml";
+pub const FRONTEND_FILE: &str = "static/index.html";
+
+static RUN_WORKSPACE: Mutex<Option<RunWorkspace>> = Mutex::new(None);
+
+// The workspace of the current run. Agents used on their own start an unnamed run on first use.
+// The run is started without holding the lock, since starting it prints agent messages and
+// those look up the active run themselves.
+pub fn run_workspace() -> RunWorkspace {
+    if let Some(run_workspace) = active_run_workspace() {
+        return run_workspace;
+    }
+
+    let started: RunWorkspace = start_run("run").expect("Couldn't start the run.");
+
+    RUN_WORKSPACE
+        .lock()
+        .expect("Run workspace lock poisoned.")
+        .get_or_insert(started)
+        .clone()
+}
+
+// Like run_workspace, but never starts a run.
+pub fn active_run_workspace() -> Option<RunWorkspace> {
+    RUN_WORKSPACE
+        .lock()
+        .ok()
+        .and_then(|run_workspace| run_workspace.clone())
+}
+
+pub fn set_run_workspace(workspace: RunWorkspace) {
+    *RUN_WORKSPACE.lock().expect("Run workspace lock poisoned.") = Some(workspace);
+}
+
+pub fn project_root() -> PathBuf {
+    run_workspace().project_dir()
+}
+
+pub fn project_path(relative_path: &str) -> PathBuf {
+    project_root().join(relative_path)
+}
 
 pub fn extend_ai_function(ai_func: fn(&str) -> &'static str, func_input: &str) -> Message {
     let ai_function_str = ai_func(func_input);
@@ -55,13 +106,23 @@ pub async fn ai_task_request_decoded<T: DeserializeOwned>(
     agent_operation: &str,
     function_pass: for<'a> fn(&'a str) -> &'static str,
 ) -> T {
+    try_ai_task_request_decoded(msg_context, agent_position, agent_operation, function_pass)
+        .await
+        .expect("Failed to decode the AI response.")
+}
+
+// For agents that can carry on without the answer or ask again.
+pub async fn try_ai_task_request_decoded<T: DeserializeOwned>(
+    msg_context: String,
+    agent_position: &str,
+    agent_operation: &str,
+    function_pass: for<'a> fn(&'a str) -> &'static str,
+) -> Result<T, String> {
     let llm_response: String =
         ai_task_request(msg_context, agent_position, agent_operation, function_pass).await;
 
-    let decoded_response: T =
-        serde_json::from_str(llm_response.as_str()).expect("Failed to decode the AI response.");
-
-    decoded_response
+    serde_json::from_str(llm_response.as_str())
+        .map_err(|e| format!("Failed to decode the AI response: {}", e))
 }
 
 pub async fn check_status_code(client: &Client, url: &str) -> Result<u16, reqwest::Error> {
@@ -69,26 +130,239 @@ pub async fn check_status_code(client: &Client, url: &str) -> Result<u16, reqwes
     Ok(response.status().as_u16())
 }
 
+// Build, lint, test and run steps execute the generated code, so they all go through the
+// configured sandbox. The run checks the configuration when it starts, and agents used on their
+// own stop here instead of falling back to running on the host.
+fn sandbox() -> Sandbox {
+    selected_sandbox()
+        .and_then(|sandbox| sandbox.check().map(|_| sandbox))
+        .unwrap_or_else(|e| panic!("Invalid sandbox configuration: {}", e))
+}
+
+pub fn build_backend_project() -> Output {
+    let sandbox: Sandbox = sandbox();
+
+    // A failed fetch shows up as missing crates in the build output.
+    let _ = sandbox.fetch_dependencies(&project_root());
+
+    sandbox
+        .output(
+            &project_root(),
+            "cargo",
+            &["build", "--message-format=json"],
+        )
+        .expect("Failed to build the backend application.")
+}
+
+pub fn fmt_check_backend_project() -> Output {
+    sandbox()
+        .output(&project_root(), "cargo", &["fmt", "--check"])
+        .expect("Failed to check the backend formatting.")
+}
+
+pub fn clippy_backend_project(deny_groups: &[String]) -> Output {
+    let mut args: Vec<String> = vec![
+        "clippy".to_string(),
+        "--message-format=json".to_string(),
+        "--".to_string(),
+    ];
+
+    for group in deny_groups {
+        args.push("-D".to_string());
+        args.push(format!("clippy::{}", group));
+    }
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    sandbox()
+        .output(&project_root(), "cargo", &args)
+        .expect("Failed to lint the backend application.")
+}
+
+pub fn test_backend_project() -> Output {
+    sandbox()
+        .output(&project_root(), "cargo", &["test"])
+        .expect("Failed to test the backend application.")
+}
+
+pub fn run_backend_project(port: u16) -> ServerHandle {
+    let project_dir: PathBuf = project_root();
+
+    sandbox()
+        .spawn_server(&project_dir, &project_dir, port)
+        .expect("Failed to run the backend application.")
+}
+
+// The fuzzer sends garbage that the server may well store, so it gets fresh copies of the data
+// files next to Cargo.toml instead of the project's own.
+pub fn run_backend_project_on_scratch_data(port: u16) -> ServerHandle {
+    let workspace: RunWorkspace = run_workspace();
+    let project_dir: PathBuf = workspace.project_dir();
+    let data_dir: PathBuf = workspace.fuzz_data_dir();
+
+    copy_data_files(&project_dir, &data_dir).expect("Failed to prepare the fuzzing data files.");
+
+    sandbox()
+        .spawn_server(&project_dir, &data_dir, port)
+        .expect("Failed to run the backend application.")
+}
+
+fn copy_data_files(project_dir: &Path, data_dir: &Path) -> std::io::Result<()> {
+    if data_dir.exists() {
+        fs::remove_dir_all(data_dir)?;
+    }
+    fs::create_dir_all(data_dir)?;
+
+    for entry in fs::read_dir(project_dir)? {
+        let path: PathBuf = entry?.path();
+
+        if path.is_file() {
+            if let Some(name) = path.file_name() {
+                fs::copy(&path, data_dir.join(name))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn read_code_template_contents() -> String {
-    let path: String = CODE_TEMPlATE_PATH.to_string();
+    let path: PathBuf = project_path(CODE_TEMPLATE_FILE);
     fs::read_to_string(path).expect("Couldn't read the file.")
 }
 
 pub fn read_executable_main_contents() -> String {
-    let path: String = EXEC_MAIN_PATH.to_string();
+    let path: PathBuf = project_path(MAIN_SOURCE_FILE);
     fs::read_to_string(path).expect("Couldn't read the file.")
 }
 
-pub fn save_backend_code(contents: &String) {
-    let path: String = EXEC_MAIN_PATH.to_string();
-    fs::write(path, contents).expect("Couldn't write to file.");
+pub fn read_project_file(relative_path: &str) -> String {
+    let path: PathBuf = project_path(relative_path);
+    fs::read_to_string(path).expect("Couldn't read the project file.")
+}
+
+pub fn save_project_file(relative_path: &str, contents: &String) {
+    let path: PathBuf = project_path(relative_path);
+    fs::write(path, contents).expect("Couldn't write to project file.");
+}
+
+// Accepts plain main.rs code or a multi-file envelope. A malformed envelope is written to
+// main.rs as is, so the build fails and the error goes back through the fix loop.
+// Every save becomes a commit in the project's history, named after the agent and the step
+// that produced the code.
+pub fn save_backend_code(contents: &String, agent_position: &str, step: &str) {
+    match FileTree::from_code(contents) {
+        Ok(file_tree) => file_tree
+            .write_atomically(&project_root())
+            .expect("Couldn't write the backend files."),
+        Err(_) => {
+            let path: PathBuf = project_path(MAIN_SOURCE_FILE);
+            fs::write(path, contents).expect("Couldn't write to file.");
+        }
+    }
+
+    let project_dir: PathBuf = project_root();
+
+    if has_history(&project_dir) {
+        if let Err(e) = commit_iteration(&project_dir, agent_position, step) {
+            PrintCommand::Issue.print_agent_msg("Project history", &e);
+        }
+    }
+}
+
+pub fn record_backend_build(success: bool) {
+    let project_dir: PathBuf = project_root();
+
+    if has_history(&project_dir) {
+        if let Err(e) = record_build_result(&project_dir, success) {
+            PrintCommand::Issue.print_agent_msg("Project history", &e);
+        }
+    }
+}
+
+// Restores the last commit that built and returns its code, or None when there is nothing to
+// go back to.
+pub fn roll_back_backend_code(agent_position: &str) -> Option<String> {
+    let project_dir: PathBuf = project_root();
+
+    if !has_history(&project_dir) {
+        return None;
+    }
+
+    let commit: String = match roll_back_to_last_build(&project_dir) {
+        Ok(Some(commit)) => commit,
+        Ok(None) => return None,
+        Err(e) => {
+            PrintCommand::Issue.print_agent_msg("Project history", &e);
+            return None;
+        }
+    };
+
+    let rollback_msg: String = format!(
+        "Rolled the backend code back to the last building commit {}",
+        &commit[..commit.len().min(10)]
+    );
+    PrintCommand::Issue.print_agent_msg(agent_position, &rollback_msg);
+
+    FileTree::read_sources(&project_dir)
+        .ok()
+        .map(|file_tree| file_tree.to_code())
+}
+
+// The database module goes through the file tree like any other backend file, so it is
+// written next to main.rs and is part of every later save and rollback.
+pub fn save_database_code(contents: &str) {
+    let mut file_tree: FileTree =
+        FileTree::read_sources(&project_root()).expect("Couldn't read the backend files.");
+    file_tree
+        .files
+        .insert(DATABASE_SOURCE_FILE.to_string(), contents.to_string());
+
+    file_tree
+        .write_atomically(&project_root())
+        .expect("Couldn't write the database module.");
+}
+
+pub fn save_frontend_code(contents: &String) {
+    let path: PathBuf = project_path(FRONTEND_FILE);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("Couldn't create the frontend directory.");
+    }
+
+    fs::write(path, contents).expect("Couldn't write to frontend file.");
+}
+
+pub fn save_readme(contents: &String) {
+    let path: PathBuf = project_path(README_FILE);
+    fs::write(path, contents).expect("Couldn't write to readme file.");
+}
+
+pub fn save_openapi_spec(contents: &String) {
+    let path: PathBuf = project_path(OPENAPI_FILE);
+    fs::write(path, contents).expect("Couldn't write to openapi file.");
 }
 
 pub fn save_api_endpoints(api_endpoints: &String) {
-    let path: String = API_SCHEMA_PATH.to_string();
+    let path: PathBuf = run_workspace().api_schema_path();
     fs::write(path, api_endpoints).expect("Couldn't write to api endpoints file.");
 }
 
+pub fn save_patch_history(patch_history: &String) {
+    let path: PathBuf = run_workspace().patch_history_path();
+    fs::write(path, patch_history).expect("Couldn't write to patch history file.");
+}
+
+pub fn save_factsheet(factsheet: &String) {
+    let path: PathBuf = run_workspace().factsheet_path();
+    fs::write(path, factsheet).expect("Couldn't write to factsheet file.");
+}
+
+pub fn save_run_report(report: &String) {
+    let path: PathBuf = run_workspace().report_path();
+    fs::write(path, report).expect("Couldn't write to run report file.");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,4 +390,33 @@ mod tests {
         dbg!(&res);
         assert!(res.len() > 20);
     }
+
+    #[test]
+    fn tests_copy_data_files() {
+        let root: PathBuf = std::env::temp_dir().join(format!("data_files_{}", std::process::id()));
+        let project_dir: PathBuf = root.join("project");
+        let data_dir: PathBuf = root.join("fuzz_data");
+
+        fs::create_dir_all(project_dir.join("src")).unwrap();
+        fs::write(project_dir.join("database.json"), "{}").unwrap();
+        fs::write(project_dir.join("src/main.rs"), "fn main() {}").unwrap();
+
+        copy_data_files(&project_dir, &data_dir).unwrap();
+        fs::write(data_dir.join("database.json"), "{\"fuzzed\": true}").unwrap();
+        fs::write(data_dir.join("leftover.json"), "[]").unwrap();
+        copy_data_files(&project_dir, &data_dir).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(data_dir.join("database.json")).unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            fs::read_to_string(project_dir.join("database.json")).unwrap(),
+            "{}"
+        );
+        assert!(!data_dir.join("leftover.json").exists());
+        assert!(!data_dir.join("src").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
 }
@@ -1,2 +1,20 @@
+pub mod code_audit;
 pub mod command_line;
+pub mod dependencies;
+pub mod diagnostics;
+pub mod docker;
+pub mod file_tree;
+pub mod framework;
+pub mod fuzzer;
 pub mod general;
+pub mod openapi;
+pub mod patch;
+pub mod project_history;
+#[cfg(unix)]
+pub mod relay;
+pub mod route_extractor;
+pub mod route_tester;
+pub mod sandbox;
+pub mod server;
+pub mod templates;
+pub mod workspace;
@@ -0,0 +1,208 @@
+use crate::models::agents::agent_traits::{FieldSchema, FieldType, RouteObject};
+use serde_json::{json, Map, Value};
+
+fn shape_to_schema(shape: &FieldSchema) -> Value {
+    match shape {
+        FieldSchema::Field(field_type) => match field_type {
+            FieldType::Number => json!({ "type": "integer" }),
+            FieldType::Float => json!({ "type": "number" }),
+            FieldType::String => json!({ "type": "string" }),
+            FieldType::Bool => json!({ "type": "boolean" }),
+            FieldType::List => json!({ "type": "array", "items": {} }),
+            FieldType::Any => json!({}),
+        },
+        FieldSchema::List(items) => json!({
+            "type": "array",
+            "items": items.first().map(shape_to_schema).unwrap_or(json!({})),
+        }),
+        FieldSchema::Object(fields) => {
+            let properties: Map<String, Value> = fields
+                .iter()
+                .map(|(name, field_shape)| (name.clone(), shape_to_schema(field_shape)))
+                .collect();
+            let required: Vec<&String> = fields
+                .iter()
+                .filter(|(_, field_shape)| !matches!(field_shape, FieldSchema::Optional(_)))
+                .map(|(name, _)| name)
+                .collect();
+
+            json!({ "type": "object", "properties": properties, "required": required })
+        }
+        FieldSchema::Optional(inner) => {
+            let mut schema: Value = shape_to_schema(inner);
+
+            if let Some(object) = schema.as_object_mut() {
+                object.insert("nullable".to_string(), json!(true));
+            }
+
+            schema
+        }
+    }
+}
+
+pub fn path_parameters(route: &str) -> Vec<String> {
+    route
+        .split('/')
+        .filter_map(|segment| {
+            segment
+                .strip_prefix('{')
+                .and_then(|rest| rest.strip_suffix('}'))
+        })
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn build_operation(route_object: &RouteObject) -> Value {
+    let parameters: Vec<Value> = path_parameters(&route_object.route)
+        .iter()
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            })
+        })
+        .collect();
+
+    let success_response: Value = match &route_object.response {
+        Some(response) => json!({
+            "description": "Successful response",
+            "content": {
+                "application/json": { "schema": shape_to_schema(response) }
+            },
+        }),
+        None => json!({ "description": "Successful response" }),
+    };
+
+    let mut operation: Value = json!({
+        "parameters": parameters,
+        "responses": { "200": success_response },
+    });
+
+    if let Some(request_body) = &route_object.request_body {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": {
+                "application/json": { "schema": shape_to_schema(request_body) }
+            },
+        });
+    }
+
+    operation
+}
+
+pub fn build_openapi_document(project_description: &str, routes: &[RouteObject]) -> Value {
+    let mut paths: Map<String, Value> = Map::new();
+
+    for route_object in routes {
+        let path_item: &mut Value = paths
+            .entry(route_object.route.clone())
+            .or_insert_with(|| json!({}));
+
+        path_item[route_object.method.to_string()] = build_operation(route_object);
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Generated web server",
+            "description": project_description,
+            "version": "0.1.0",
+        },
+        "servers": [{ "url": "http://localhost:8080" }],
+        "paths": paths,
+    })
+}
+
+// Parses the document with the OpenAPI 3 model and checks every templated path parameter is declared.
+pub fn validate_openapi_yaml(openapi_yaml: &str) -> Result<(), String> {
+    let document: openapiv3::OpenAPI = serde_yaml::from_str(openapi_yaml)
+        .map_err(|e| format!("Invalid OpenAPI 3 document: {}", e))?;
+
+    if !document.openapi.starts_with("3.") {
+        return Err(format!("Unsupported OpenAPI version: {}", document.openapi));
+    }
+
+    for (path, path_item) in document.paths.iter() {
+        let path_item = path_item
+            .as_item()
+            .ok_or(format!("Path {} must not be a reference", path))?;
+
+        for (method, operation) in path_item.iter() {
+            let declared: Vec<&str> = operation
+                .parameters
+                .iter()
+                .filter_map(|parameter| parameter.as_item())
+                .map(|parameter| parameter.parameter_data_ref().name.as_str())
+                .collect();
+
+            for name in path_parameters(path) {
+                if !declared.contains(&name.as_str()) {
+                    return Err(format!(
+                        "{} {} does not declare path parameter {}",
+                        method, path, name
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agents::agent_traits::HttpMethod;
+
+    #[test]
+    fn tests_build_and_validate_openapi() {
+        let item = json!({ "id": "number", "name": "string", "completed": "bool" });
+
+        let routes: Vec<RouteObject> = vec![
+            RouteObject {
+                is_route_dynamic: true,
+                method: HttpMethod::Get,
+                request_body: None,
+                response: FieldSchema::from_value(&item),
+                route: "/item/{id}".to_string(),
+            },
+            RouteObject {
+                is_route_dynamic: false,
+                method: HttpMethod::Post,
+                request_body: FieldSchema::from_value(&item),
+                response: None,
+                route: "/item".to_string(),
+            },
+        ];
+
+        let document: Value = build_openapi_document("build a todo website", &routes);
+        assert_eq!(
+            document["paths"]["/item/{id}"]["get"]["responses"]["200"]["content"]
+                ["application/json"]["schema"]["properties"]["completed"]["type"],
+            "boolean"
+        );
+
+        let openapi_yaml: String = serde_yaml::to_string(&document).unwrap();
+        assert_eq!(validate_openapi_yaml(&openapi_yaml), Ok(()));
+    }
+
+    #[test]
+    fn tests_validate_openapi_missing_parameter() {
+        let openapi_yaml: &str = r#"
+openapi: 3.0.3
+info:
+  title: test
+  version: 0.1.0
+paths:
+  /item/{id}:
+    get:
+      responses:
+        "200":
+          description: ok
+"#;
+
+        assert!(validate_openapi_yaml(openapi_yaml).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER_MARKER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+// Share of SEARCH lines that must match (ignoring indentation) for a fuzzy match to be accepted.
+const FUZZY_MATCH_THRESHOLD: f64 = 0.8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditBlock {
+    pub search: String,
+    pub replace: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatchRecord {
+    pub step: String,
+    pub edits: Vec<EditBlock>,
+    pub fell_back_to_rewrite: bool,
+    pub failure: Option<String>,
+}
+
+// Reads SEARCH/REPLACE blocks:
+//   <<<<<<< SEARCH
+//   old lines
+//   =======
+//   new lines
+//   >>>>>>> REPLACE
+pub fn parse_edit_blocks(text: &str) -> Result<Vec<EditBlock>, String> {
+    let mut blocks: Vec<EditBlock> = vec![];
+    let mut lines = text.lines();
+
+    while let Some(line) = lines.next() {
+        if line.trim() != SEARCH_MARKER {
+            continue;
+        }
+
+        let mut search: Vec<&str> = vec![];
+        let mut replace: Vec<&str> = vec![];
+        let mut in_replace: bool = false;
+        let mut closed: bool = false;
+
+        for line in lines.by_ref() {
+            match line.trim() {
+                DIVIDER_MARKER if !in_replace => in_replace = true,
+                REPLACE_MARKER if in_replace => {
+                    closed = true;
+                    break;
+                }
+                _ if in_replace => replace.push(line),
+                _ => search.push(line),
+            }
+        }
+
+        if !closed {
+            return Err(format!(
+                "Edit block {} is not closed with {}",
+                blocks.len() + 1,
+                REPLACE_MARKER
+            ));
+        }
+
+        blocks.push(EditBlock {
+            search: search.join("\n"),
+            replace: replace.join("\n"),
+        });
+    }
+
+    if blocks.is_empty() {
+        return Err("No edit blocks found".to_string());
+    }
+
+    Ok(blocks)
+}
+
+fn line_offsets(source: &str) -> Vec<usize> {
+    let mut offsets: Vec<usize> = vec![0];
+    offsets.extend(source.match_indices('\n').map(|(index, _)| index + 1));
+    offsets
+}
+
+// Finds the byte range of the source lines that best match `search`, ignoring indentation
+// and trailing whitespace. Used when the LLM did not reproduce the code exactly. Two places
+// matching equally well are as useless as none, since either could be the intended one.
+fn fuzzy_find(source: &str, search: &str) -> Result<(usize, usize), String> {
+    let source_lines: Vec<&str> = source.lines().collect();
+    let search_lines: Vec<&str> = search.lines().map(str::trim).collect();
+
+    if search_lines.is_empty() || search_lines.len() > source_lines.len() {
+        return Err("does not match the code".to_string());
+    }
+
+    let offsets: Vec<usize> = line_offsets(source);
+    let mut best: Option<(usize, f64)> = None;
+    let mut ties: usize = 0;
+
+    for start in 0..=source_lines.len() - search_lines.len() {
+        let matching: usize = search_lines
+            .iter()
+            .zip(&source_lines[start..])
+            .filter(|(search_line, source_line)| **search_line == source_line.trim())
+            .count();
+
+        let score: f64 = matching as f64 / search_lines.len() as f64;
+
+        match best {
+            Some((_, best_score)) if score == best_score => ties += 1,
+            Some((_, best_score)) if score < best_score => {}
+            _ => {
+                best = Some((start, score));
+                ties = 0;
+            }
+        }
+    }
+
+    let (start, score) = match best {
+        Some((start, score)) if score >= FUZZY_MATCH_THRESHOLD => (start, score),
+        _ => return Err("does not match the code".to_string()),
+    };
+
+    if ties > 0 {
+        return Err(format!(
+            "matches {} places equally well ({:.0}% of lines)",
+            ties + 1,
+            score * 100.0
+        ));
+    }
+
+    let last_line: usize = start + search_lines.len() - 1;
+    let end: usize = offsets[last_line] + source_lines[last_line].len();
+
+    Ok((offsets[start], end))
+}
+
+pub fn apply_edit_blocks(source: &str, blocks: &[EditBlock]) -> Result<String, String> {
+    let mut patched: String = source.to_string();
+
+    for (index, block) in blocks.iter().enumerate() {
+        if block.search.trim().is_empty() {
+            return Err(format!(
+                "Edit block {} has an empty SEARCH section",
+                index + 1
+            ));
+        }
+
+        let exact_matches: Vec<usize> = patched
+            .match_indices(&block.search)
+            .map(|(start, _)| start)
+            .collect();
+
+        let range: Result<(usize, usize), String> = match exact_matches.as_slice() {
+            [start] => Ok((*start, start + block.search.len())),
+            [] => fuzzy_find(&patched, &block.search),
+            _ => Err(format!("matches {} places", exact_matches.len())),
+        };
+
+        match range {
+            Ok((start, end)) => patched.replace_range(start..end, &block.replace),
+            Err(reason) => {
+                return Err(format!(
+                    "Edit block {} {}:\n{}",
+                    index + 1,
+                    reason,
+                    block.search
+                ))
+            }
+        }
+    }
+
+    Ok(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "async fn read_item(id: web::Path<u64>) -> impl Responder {\n    let db = Database::load();\n    HttpResponse::Ok().json(db.get(&id))\n}\n\nasync fn delete_item() -> impl Responder {\n    HttpResponse::Ok().finish()\n}\n";
+
+    #[test]
+    fn tests_parse_edit_blocks() {
+        let response: &str = "Here are the edits:\n<<<<<<< SEARCH\n    let db = Database::load();\n=======\n    let db = data.lock().unwrap();\n>>>>>>> REPLACE\n";
+
+        assert_eq!(
+            parse_edit_blocks(response),
+            Ok(vec![EditBlock {
+                search: "    let db = Database::load();".to_string(),
+                replace: "    let db = data.lock().unwrap();".to_string(),
+            }])
+        );
+
+        assert!(parse_edit_blocks("fn main() {}").is_err());
+        assert!(parse_edit_blocks("<<<<<<< SEARCH\na\n=======\nb\n").is_err());
+    }
+
+    #[test]
+    fn tests_apply_edit_blocks() {
+        let exact: EditBlock = EditBlock {
+            search: "    HttpResponse::Ok().finish()".to_string(),
+            replace: "    HttpResponse::NoContent().finish()".to_string(),
+        };
+
+        // Indentation lost on the way through the LLM: still found by the fuzzy match.
+        let fuzzy: EditBlock = EditBlock {
+            search: "async fn read_item(id: web::Path<u64>) -> impl Responder {\nlet db = Database::load();\nHttpResponse::Ok().json(db.get(&id))\n}\n".to_string(),
+            replace: "async fn read_item(id: web::Path<u64>) -> impl Responder {\n    HttpResponse::Ok().json(id.into_inner())\n}".to_string(),
+        };
+
+        let patched: String = apply_edit_blocks(SOURCE, &[exact, fuzzy]).unwrap();
+
+        assert_eq!(
+            patched,
+            "async fn read_item(id: web::Path<u64>) -> impl Responder {\n    HttpResponse::Ok().json(id.into_inner())\n}\n\nasync fn delete_item() -> impl Responder {\n    HttpResponse::NoContent().finish()\n}\n"
+        );
+
+        let missing: EditBlock = EditBlock {
+            search: "fn create_item() {}".to_string(),
+            replace: String::new(),
+        };
+        assert!(apply_edit_blocks(SOURCE, &[missing]).is_err());
+    }
+
+    #[test]
+    fn tests_ambiguous_edit_blocks() {
+        let repeated: EditBlock = EditBlock {
+            search: "impl Responder {".to_string(),
+            replace: "impl Responder + Send {".to_string(),
+        };
+        let error: String = apply_edit_blocks(SOURCE, &[repeated]).unwrap_err();
+        assert!(error.starts_with("Edit block 1 matches 2 places"));
+
+        // Only the function name differs, so both functions match four of the five lines.
+        let source: &str = "fn a() {\n    let x = 1;\n    one(x);\n    two(x);\n}\n\nfn b() {\n    let x = 1;\n    one(x);\n    two(x);\n}\n";
+        let tied: EditBlock = EditBlock {
+            search: "fn c() {\nlet x = 1;\none(x);\ntwo(x);\n}".to_string(),
+            replace: "fn c() {}".to_string(),
+        };
+        let error: String = apply_edit_blocks(source, &[tied]).unwrap_err();
+        assert!(error.starts_with("Edit block 1 matches 2 places equally well"));
+    }
+}
@@ -0,0 +1,213 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// Trailer on every iteration commit. It starts as pending and is amended once the build ran.
+const BUILD_PENDING: &str = "Build: pending";
+const BUILD_SUCCEEDED: &str = "Build: succeeded";
+const BUILD_FAILED: &str = "Build: failed";
+
+const PROJECT_GITIGNORE: &str = "target/\n*.auto_gpt_staging\n";
+
+// The repository lives next to the project instead of in it. The sandbox mounts the project
+// writable, and hooks or config written into a .git there would run on the host at the next
+// commit.
+fn history_dir(project_dir: &Path) -> PathBuf {
+    project_dir.with_extension("git")
+}
+
+// The generated project is versioned with its own repository and a fixed identity, so commits
+// work on machines without a global git config.
+fn git(project_dir: &Path, args: &[&str]) -> Result<String, String> {
+    let mut git_dir: std::ffi::OsString = "--git-dir=".into();
+    git_dir.push(history_dir(project_dir));
+
+    let mut work_tree: std::ffi::OsString = "--work-tree=".into();
+    work_tree.push(project_dir);
+
+    let output: Output = Command::new("git")
+        .arg(git_dir)
+        .arg(work_tree)
+        .args([
+            "-c",
+            "user.name=auto_gpt",
+            "-c",
+            "user.email=auto_gpt@localhost",
+            "-c",
+            "commit.gpgsign=false",
+            "-c",
+            "core.fsmonitor=false",
+        ])
+        .args(args)
+        .current_dir(project_dir)
+        .output()
+        .map_err(|e| format!("Couldn't run git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn has_history(project_dir: &Path) -> bool {
+    history_dir(project_dir).is_dir()
+}
+
+pub fn init_history(project_dir: &Path, template: &str) -> Result<(), String> {
+    git(project_dir, &["init", "--quiet"])?;
+
+    fs::write(project_dir.join(".gitignore"), PROJECT_GITIGNORE)
+        .map_err(|e| format!("Couldn't write the project .gitignore: {}", e))?;
+
+    git(project_dir, &["add", "--all"])?;
+    git(
+        project_dir,
+        &[
+            "commit",
+            "--quiet",
+            "-m",
+            &format!("Template {}\n\n{}", template, BUILD_PENDING),
+        ],
+    )?;
+
+    Ok(())
+}
+
+// Commits the project as it is on disk. Saves that change nothing still get a commit, so the
+// history lists every step.
+pub fn commit_iteration(
+    project_dir: &Path,
+    agent_position: &str,
+    step: &str,
+) -> Result<(), String> {
+    git(project_dir, &["add", "--all"])?;
+    git(
+        project_dir,
+        &[
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "-m",
+            &format!("{}: {}\n\n{}", agent_position, step, BUILD_PENDING),
+        ],
+    )?;
+
+    Ok(())
+}
+
+// Replaces the pending trailer of the latest commit. Only the message changes.
+pub fn record_build_result(project_dir: &Path, success: bool) -> Result<(), String> {
+    let message: String = git(project_dir, &["log", "-1", "--format=%B"])?;
+
+    if !message.ends_with(BUILD_PENDING) {
+        return Ok(());
+    }
+
+    let result: &str = if success {
+        BUILD_SUCCEEDED
+    } else {
+        BUILD_FAILED
+    };
+
+    let amended: String = format!("{}{}", message.trim_end_matches(BUILD_PENDING), result);
+    git(
+        project_dir,
+        &["commit", "--quiet", "--amend", "--only", "-m", &amended],
+    )?;
+
+    Ok(())
+}
+
+pub fn last_building_commit(project_dir: &Path) -> Result<Option<String>, String> {
+    let commit: String = git(
+        project_dir,
+        &[
+            "log",
+            "-1",
+            "--format=%H",
+            &format!("--grep=^{}$", BUILD_SUCCEEDED),
+        ],
+    )?;
+
+    Ok((!commit.is_empty()).then_some(commit))
+}
+
+// Restores the files of `commit` on top of the current history, so the failed attempts stay
+// reviewable.
+pub fn roll_back_to(project_dir: &Path, commit: &str) -> Result<(), String> {
+    git(
+        project_dir,
+        &["restore", "--source", commit, "--staged", "--worktree", "."],
+    )?;
+
+    let short: &str = &commit[..commit.len().min(10)];
+    git(
+        project_dir,
+        &[
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "-m",
+            &format!("Roll back to {}\n\n{}", short, BUILD_SUCCEEDED),
+        ],
+    )?;
+
+    Ok(())
+}
+
+// Returns the commit that was restored, or None when nothing ever built.
+pub fn roll_back_to_last_build(project_dir: &Path) -> Result<Option<String>, String> {
+    let Some(commit) = last_building_commit(project_dir)? else {
+        return Ok(None);
+    };
+
+    roll_back_to(project_dir, &commit)?;
+    Ok(Some(commit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_roll_back_to_last_build() {
+        let project_dir: PathBuf =
+            std::env::temp_dir().join(format!("project_history_{}", std::process::id()));
+        fs::create_dir_all(project_dir.join("src")).unwrap();
+        fs::write(project_dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        init_history(&project_dir, "actix-json-db").unwrap();
+        assert_eq!(last_building_commit(&project_dir), Ok(None));
+        assert!(has_history(&project_dir));
+        assert!(!project_dir.join(".git").exists());
+
+        fs::write(project_dir.join("src/main.rs"), "fn main() { good() }\n").unwrap();
+        commit_iteration(&project_dir, "Backend developer", "print_fixed_code").unwrap();
+        record_build_result(&project_dir, true).unwrap();
+
+        fs::write(project_dir.join("src/main.rs"), "fn main() { broken( }\n").unwrap();
+        fs::write(project_dir.join("src/extra.rs"), "pub fn extra() {}\n").unwrap();
+        commit_iteration(&project_dir, "Backend developer", "print_fixed_code").unwrap();
+        record_build_result(&project_dir, false).unwrap();
+
+        let log: String = git(&project_dir, &["log", "--format=%B"]).unwrap();
+        assert!(log.starts_with("Backend developer: print_fixed_code\n\nBuild: failed"));
+
+        let restored: Option<String> = roll_back_to_last_build(&project_dir).unwrap();
+
+        assert!(restored.is_some());
+        assert_eq!(
+            fs::read_to_string(project_dir.join("src/main.rs")).unwrap(),
+            "fn main() { good() }\n"
+        );
+        assert!(!project_dir.join("src/extra.rs").exists());
+
+        fs::remove_dir_all(history_dir(&project_dir)).unwrap();
+        fs::remove_dir_all(project_dir).unwrap();
+    }
+}
@@ -0,0 +1,169 @@
+use std::fs;
+use std::io;
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+// The hidden command the sandboxed server is started through, see `serve_relayed`.
+pub const SERVE_RELAYED_COMMAND: &str = "__sandbox-serve";
+pub const RELAY_SOCKET: &str = "server.sock";
+
+// Copies both directions until either side closes.
+fn join_streams(tcp: TcpStream, unix: UnixStream) -> io::Result<()> {
+    let mut tcp_reader: TcpStream = tcp.try_clone()?;
+    let mut unix_writer: UnixStream = unix.try_clone()?;
+
+    thread::spawn(move || {
+        let _ = io::copy(&mut tcp_reader, &mut unix_writer);
+        let _ = unix_writer.shutdown(Shutdown::Write);
+    });
+
+    thread::spawn(move || {
+        let (mut unix_reader, mut tcp_writer) = (unix, tcp);
+        let _ = io::copy(&mut unix_reader, &mut tcp_writer);
+        let _ = tcp_writer.shutdown(Shutdown::Write);
+    });
+
+    Ok(())
+}
+
+// Host side of the relay: 127.0.0.1:port on the host forwards to the unix socket in `directory`,
+// which the sandbox sees at /tmp/relay. Stopping it removes the directory.
+pub struct PortRelay {
+    port: u16,
+    directory: PathBuf,
+    stopped: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl PortRelay {
+    pub fn start(port: u16, directory: &Path) -> io::Result<Self> {
+        let listener: TcpListener = TcpListener::bind(("127.0.0.1", port))?;
+        let socket: PathBuf = directory.join(RELAY_SOCKET);
+        let stopped: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let stopped_flag: Arc<AtomicBool> = Arc::clone(&stopped);
+
+        let listener: JoinHandle<()> = thread::spawn(move || {
+            for tcp in listener.incoming() {
+                if stopped_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                // Until the server created its socket, connections are dropped like a refused one.
+                if let (Ok(tcp), Ok(unix)) = (tcp, UnixStream::connect(&socket)) {
+                    let _ = join_streams(tcp, unix);
+                }
+            }
+        });
+
+        Ok(Self {
+            port,
+            directory: directory.to_path_buf(),
+            stopped,
+            listener: Some(listener),
+        })
+    }
+
+    pub fn stop(&mut self) {
+        let Some(listener) = self.listener.take() else {
+            return;
+        };
+
+        self.stopped.store(true, Ordering::SeqCst);
+
+        // Wakes the blocking accept so the listener sees the flag.
+        let _ = TcpStream::connect(("127.0.0.1", self.port));
+        let _ = listener.join();
+        let _ = fs::remove_dir_all(&self.directory);
+    }
+}
+
+impl Drop for PortRelay {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// Sandbox side of the relay: every connection to the unix socket is forwarded to the server on
+// the sandbox's own loopback.
+fn relay_socket_to_port(listener: UnixListener, port: u16) {
+    for unix in listener.incoming() {
+        if let (Ok(unix), Ok(tcp)) = (unix, TcpStream::connect(("127.0.0.1", port))) {
+            let _ = join_streams(tcp, unix);
+        }
+    }
+}
+
+// Runs inside the sandbox as `__sandbox-serve <socket> <port> <program> [args]`: starts the
+// program and relays the socket to its port until it exits. Returns the program's exit code.
+pub fn serve_relayed(args: &[String]) -> i32 {
+    let (socket, port, program, program_args) = match args {
+        [socket, port, program, program_args @ ..] => match port.parse::<u16>() {
+            Ok(port) => (socket, port, program, program_args),
+            Err(_) => return 2,
+        },
+        _ => return 2,
+    };
+
+    let listener: UnixListener = match UnixListener::bind(socket) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Couldn't open the relay socket {}: {}", socket, e);
+            return 1;
+        }
+    };
+
+    thread::spawn(move || relay_socket_to_port(listener, port));
+
+    match Command::new(program).args(program_args).status() {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            eprintln!("Couldn't start {}: {}", program, e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn tests_port_relay() {
+        let relay_dir: PathBuf =
+            env::temp_dir().join(format!("sandbox_relay_{}", std::process::id()));
+        fs::create_dir_all(&relay_dir).unwrap();
+
+        // Stands in for the sandboxed server: echoes one message back.
+        let server: TcpListener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let server_port: u16 = server.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let (mut stream, _) = server.accept().unwrap();
+            let mut buffer: [u8; 5] = [0; 5];
+            stream.read_exact(&mut buffer).unwrap();
+            stream.write_all(&buffer).unwrap();
+        });
+
+        let socket: UnixListener = UnixListener::bind(relay_dir.join(RELAY_SOCKET)).unwrap();
+        thread::spawn(move || relay_socket_to_port(socket, server_port));
+
+        let host_port: u16 = crate::helpers::server::find_free_port().unwrap();
+        let mut relay: PortRelay = PortRelay::start(host_port, &relay_dir).unwrap();
+
+        let mut client: TcpStream = TcpStream::connect(("127.0.0.1", host_port)).unwrap();
+        client.write_all(b"hello").unwrap();
+        let mut reply: String = String::new();
+        client.read_to_string(&mut reply).unwrap();
+
+        assert_eq!(reply, "hello");
+
+        relay.stop();
+        assert!(!relay_dir.exists());
+    }
+}
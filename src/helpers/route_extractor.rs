@@ -0,0 +1,881 @@
+use crate::models::agents::agent_traits::{FieldSchema, FieldType, HttpMethod, RouteObject};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use syn::visit::{self, Visit};
+
+// Routes the static pass found, plus the ones whose response shape it could not work out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedRoutes {
+    pub routes: Vec<RouteObject>,
+    pub unresolved: Vec<(String, HttpMethod)>,
+}
+
+// Nested routers can go through a handful of helper functions, but not through a cycle.
+const MAX_MOUNT_DEPTH: usize = 8;
+
+#[derive(Debug, Clone)]
+struct RouteBinding {
+    route: String,
+    method: HttpMethod,
+    handler: String,
+    // The function or local the route was registered in, so the prefix it is mounted under
+    // can be added once everything was visited.
+    origin: Option<String>,
+}
+
+// Where a router, scope config or attribute handler was passed to `nest`, `service` or
+// `configure`.
+#[derive(Debug, Clone)]
+struct Mount {
+    prefix: String,
+    origin: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum BodyShape {
+    Resolved(Option<FieldSchema>),
+    Unresolved,
+}
+
+#[derive(Default)]
+struct CodeIndex<'ast> {
+    structs: HashMap<String, &'ast syn::ItemStruct>,
+    handlers: HashMap<String, &'ast syn::ItemFn>,
+    bindings: Vec<RouteBinding>,
+    mounts: HashMap<String, Mount>,
+    // Locals that hold a router, with the function they are defined in.
+    locals: HashMap<String, String>,
+    // The `web::scope` and `nest` prefixes around the expression being visited.
+    prefix: String,
+    origin: Option<String>,
+}
+
+fn last_segment(path: &syn::Path) -> Option<String> {
+    path.segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+}
+
+fn generic_arguments(segment: &syn::PathSegment) -> Vec<&syn::Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                syn::GenericArgument::Type(inner) => Some(inner),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn lit_str(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) => Some(lit.value()),
+        _ => None,
+    }
+}
+
+// Finds `web::get().to(handler)` and returns (get, handler).
+fn method_and_handler(expr: &syn::Expr) -> Option<(HttpMethod, String)> {
+    let syn::Expr::MethodCall(to_call) = expr else {
+        return None;
+    };
+
+    if to_call.method != "to" {
+        return None;
+    }
+
+    let handler: String = match to_call.args.first()? {
+        syn::Expr::Path(handler_path) => last_segment(&handler_path.path)?,
+        _ => return None,
+    };
+
+    let syn::Expr::Call(method_call) = to_call.receiver.as_ref() else {
+        return None;
+    };
+
+    let syn::Expr::Path(method_path) = method_call.func.as_ref() else {
+        return None;
+    };
+
+    let method: HttpMethod = HttpMethod::from_str(&last_segment(&method_path.path)?).ok()?;
+    Some((method, handler))
+}
+
+// Finds axum's `get(handler).post(other_handler)` method routers, in chain order.
+fn method_router(expr: &syn::Expr) -> Vec<(HttpMethod, String)> {
+    let (name, argument, receiver) = match expr {
+        syn::Expr::MethodCall(method_call) => (
+            Some(method_call.method.to_string()),
+            method_call.args.first(),
+            Some(method_call.receiver.as_ref()),
+        ),
+        syn::Expr::Call(call) => match call.func.as_ref() {
+            syn::Expr::Path(func) => (last_segment(&func.path), call.args.first(), None),
+            _ => return vec![],
+        },
+        _ => return vec![],
+    };
+
+    let Some(Ok(method)) = name.map(|name| HttpMethod::from_str(&name)) else {
+        return vec![];
+    };
+
+    let Some(syn::Expr::Path(handler_path)) = argument else {
+        return vec![];
+    };
+
+    let Some(handler) = last_segment(&handler_path.path) else {
+        return vec![];
+    };
+
+    let mut methods: Vec<(HttpMethod, String)> = receiver.map(method_router).unwrap_or_default();
+    methods.push((method, handler));
+    methods
+}
+
+// `web::scope("/api")` at the root of an actix builder chain.
+fn scope_path(expr: &syn::Expr) -> Option<String> {
+    let syn::Expr::Call(call) = expr else {
+        return None;
+    };
+
+    match call.func.as_ref() {
+        syn::Expr::Path(func) if last_segment(&func.path).as_deref() == Some("scope") => {
+            lit_str(call.args.first()?)
+        }
+        _ => None,
+    }
+}
+
+// A router or config passed by name, as `api_routes` or `api_routes()`.
+fn mounted_name(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Path(path) => last_segment(&path.path),
+        syn::Expr::Call(call) => match call.func.as_ref() {
+            syn::Expr::Path(func) => last_segment(&func.path),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn join_route(prefix: &str, route: &str) -> String {
+    let prefix: &str = prefix.trim_end_matches('/');
+
+    match route {
+        "" | "/" if !prefix.is_empty() => prefix.to_string(),
+        _ if route.is_empty() || route.starts_with('/') => format!("{}{}", prefix, route),
+        _ => format!("{}/{}", prefix, route),
+    }
+}
+
+// Walks down a builder chain to the path given to `web::resource("/path")`.
+fn resource_path(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::MethodCall(method_call) => resource_path(&method_call.receiver),
+        syn::Expr::Call(call) => match call.func.as_ref() {
+            syn::Expr::Path(func) if last_segment(&func.path).as_deref() == Some("resource") => {
+                lit_str(call.args.first()?)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl<'ast> Visit<'ast> for CodeIndex<'ast> {
+    fn visit_item_struct(&mut self, item_struct: &'ast syn::ItemStruct) {
+        self.structs
+            .insert(item_struct.ident.to_string(), item_struct);
+        visit::visit_item_struct(self, item_struct);
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        let handler: String = item_fn.sig.ident.to_string();
+
+        for attribute in &item_fn.attrs {
+            let Some(name) = last_segment(attribute.path()) else {
+                continue;
+            };
+
+            let Ok(method) = HttpMethod::from_str(&name) else {
+                continue;
+            };
+
+            // Attribute routes take the prefix of the scope the handler is registered in.
+            if let Ok(route) = attribute.parse_args::<syn::LitStr>() {
+                self.bindings.push(RouteBinding {
+                    route: route.value(),
+                    method,
+                    handler: handler.clone(),
+                    origin: Some(handler.clone()),
+                });
+            }
+        }
+
+        self.handlers.insert(handler.clone(), item_fn);
+
+        let outer_origin: Option<String> = self.origin.replace(handler);
+        visit::visit_item_fn(self, item_fn);
+        self.origin = outer_origin;
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        let name: Option<String> = match &local.pat {
+            syn::Pat::Ident(pat_ident) => Some(pat_ident.ident.to_string()),
+            syn::Pat::Type(pat_type) => match pat_type.pat.as_ref() {
+                syn::Pat::Ident(pat_ident) => Some(pat_ident.ident.to_string()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let Some(name) = name.filter(|_| local.init.is_some()) else {
+            visit::visit_local(self, local);
+            return;
+        };
+
+        if let Some(function) = &self.origin {
+            self.locals.insert(name.clone(), function.clone());
+        }
+
+        let outer_origin: Option<String> = self.origin.replace(name);
+        visit::visit_local(self, local);
+        self.origin = outer_origin;
+    }
+
+    // Builder chains are walked from the innermost call out, so a `web::scope("/api")` at the
+    // root prefixes every route registered further along the chain.
+    fn visit_expr_method_call(&mut self, method_call: &'ast syn::ExprMethodCall) {
+        let mut chain: Vec<&'ast syn::ExprMethodCall> = vec![method_call];
+
+        while let syn::Expr::MethodCall(receiver) = chain[chain.len() - 1].receiver.as_ref() {
+            chain.push(receiver);
+        }
+
+        let root: &'ast syn::Expr = chain[chain.len() - 1].receiver.as_ref();
+        self.visit_expr(root);
+
+        let outer_prefix: String = self.prefix.clone();
+
+        if let Some(scope) = scope_path(root) {
+            self.prefix = join_route(&outer_prefix, &scope);
+        }
+
+        for call in chain.into_iter().rev() {
+            self.visit_chain_call(call);
+        }
+
+        self.prefix = outer_prefix;
+    }
+}
+
+impl<'ast> CodeIndex<'ast> {
+    fn visit_chain_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let arguments: Vec<&'ast syn::Expr> = call.args.iter().collect();
+
+        match (call.method.to_string().as_str(), arguments.as_slice()) {
+            ("route", _) => self.record_route(call, &arguments),
+            ("nest", [path, router]) => {
+                if let Some(path) = lit_str(path) {
+                    let outer_prefix: String = self.prefix.clone();
+                    self.prefix = join_route(&outer_prefix, &path);
+                    self.mount(router);
+                    self.visit_expr(router);
+                    self.prefix = outer_prefix;
+                    return;
+                }
+            }
+            ("service" | "configure", [target]) => self.mount(target),
+            _ => {}
+        }
+
+        for argument in arguments {
+            self.visit_expr(argument);
+        }
+    }
+
+    fn record_route(&mut self, call: &syn::ExprMethodCall, arguments: &[&syn::Expr]) {
+        let (route, methods) = match arguments {
+            [path, target] => (
+                lit_str(path),
+                method_and_handler(target)
+                    .map(|binding| vec![binding])
+                    .unwrap_or_else(|| method_router(target)),
+            ),
+            [target] => (
+                resource_path(&call.receiver),
+                method_and_handler(target).into_iter().collect(),
+            ),
+            _ => (None, vec![]),
+        };
+
+        if let Some(route) = route {
+            for (method, handler) in methods {
+                self.bindings.push(RouteBinding {
+                    route: join_route(&self.prefix, &route),
+                    method,
+                    handler,
+                    origin: self.origin.clone(),
+                });
+            }
+        }
+    }
+
+    fn mount(&mut self, target: &syn::Expr) {
+        if let Some(name) = mounted_name(target) {
+            self.mounts.entry(name).or_insert_with(|| Mount {
+                prefix: self.prefix.clone(),
+                origin: self.origin.clone(),
+            });
+        }
+    }
+
+    // The prefixes of every `nest`, `scope` and `configure` the origin was mounted under,
+    // outermost first.
+    fn mounted_prefix(&self, origin: Option<&String>) -> String {
+        let mut prefix: String = String::new();
+        let mut origin: Option<String> = origin.cloned();
+
+        for _ in 0..MAX_MOUNT_DEPTH {
+            let Some(name) = origin else {
+                break;
+            };
+
+            origin = match self.mounts.get(&name) {
+                Some(mount) => {
+                    prefix = join_route(&mount.prefix, &prefix);
+                    mount.origin.clone()
+                }
+                None => self.locals.get(&name).cloned(),
+            };
+        }
+
+        prefix
+    }
+}
+
+impl<'ast> CodeIndex<'ast> {
+    fn type_schema(&self, ty: &syn::Type, depth: usize) -> FieldSchema {
+        let syn::Type::Path(type_path) = ty else {
+            return match ty {
+                syn::Type::Reference(reference) => self.type_schema(&reference.elem, depth),
+                _ => FieldSchema::Field(FieldType::Any),
+            };
+        };
+
+        let Some(segment) = type_path.path.segments.last() else {
+            return FieldSchema::Field(FieldType::Any);
+        };
+
+        let name: String = segment.ident.to_string();
+        let inner: Vec<&syn::Type> = generic_arguments(segment);
+
+        match name.as_str() {
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+            | "i128" | "isize" => FieldSchema::Field(FieldType::Number),
+            "f32" | "f64" => FieldSchema::Field(FieldType::Float),
+            "String" | "str" => FieldSchema::Field(FieldType::String),
+            "bool" => FieldSchema::Field(FieldType::Bool),
+            "Vec" | "VecDeque" | "HashSet" => match inner.first() {
+                Some(item) => FieldSchema::List(vec![self.type_schema(item, depth)]),
+                None => FieldSchema::Field(FieldType::List),
+            },
+            "Option" => match inner.first() {
+                Some(item) => FieldSchema::Optional(Box::new(self.type_schema(item, depth))),
+                None => FieldSchema::Field(FieldType::Any),
+            },
+            "Box" | "Json" => match inner.first() {
+                Some(item) => self.type_schema(item, depth),
+                None => FieldSchema::Field(FieldType::Any),
+            },
+            _ => self.struct_schema(&name, depth),
+        }
+    }
+
+    fn struct_schema(&self, name: &str, depth: usize) -> FieldSchema {
+        // Recursive structs would otherwise never terminate.
+        if depth > 4 {
+            return FieldSchema::Field(FieldType::Any);
+        }
+
+        let Some(item_struct) = self.structs.get(name) else {
+            return FieldSchema::Field(FieldType::Any);
+        };
+
+        let fields: BTreeMap<String, FieldSchema> = item_struct
+            .fields
+            .iter()
+            .filter_map(|field| {
+                field
+                    .ident
+                    .as_ref()
+                    .map(|ident| (ident.to_string(), self.type_schema(&field.ty, depth + 1)))
+            })
+            .collect();
+
+        FieldSchema::Object(fields)
+    }
+
+    fn request_body(&self, handler: &syn::ItemFn) -> Option<FieldSchema> {
+        handler.sig.inputs.iter().find_map(|input| {
+            let syn::FnArg::Typed(pat_type) = input else {
+                return None;
+            };
+
+            let syn::Type::Path(type_path) = pat_type.ty.as_ref() else {
+                return None;
+            };
+
+            let segment: &syn::PathSegment = type_path.path.segments.last()?;
+
+            if segment.ident != "Json" {
+                return None;
+            }
+
+            generic_arguments(segment)
+                .first()
+                .map(|inner| self.type_schema(inner, 0))
+        })
+    }
+
+    fn response(&self, handler: &syn::ItemFn) -> BodyShape {
+        if let syn::ReturnType::Type(_, return_type) = &handler.sig.output {
+            if let Some(schema) = self.json_return_type(return_type) {
+                return BodyShape::Resolved(Some(schema));
+            }
+        }
+
+        let mut finder: JsonBodyFinder = JsonBodyFinder::default();
+        finder.visit_block(&handler.block);
+
+        match finder.json_bodies.first() {
+            None => BodyShape::Resolved(None),
+            Some(expr) => match self.expr_schema(expr, &finder.locals) {
+                Some(schema) => BodyShape::Resolved(Some(schema)),
+                None => BodyShape::Unresolved,
+            },
+        }
+    }
+
+    // `web::Json<T>` and `Result<web::Json<T>, E>` return types.
+    fn json_return_type(&self, ty: &syn::Type) -> Option<FieldSchema> {
+        let syn::Type::Path(type_path) = ty else {
+            return None;
+        };
+
+        let segment: &syn::PathSegment = type_path.path.segments.last()?;
+        let inner: Vec<&syn::Type> = generic_arguments(segment);
+
+        match segment.ident.to_string().as_str() {
+            "Json" => inner.first().map(|item| self.type_schema(item, 0)),
+            "Result" => inner.first().and_then(|ok| self.json_return_type(ok)),
+            _ => None,
+        }
+    }
+
+    fn expr_schema(
+        &self,
+        expr: &syn::Expr,
+        locals: &HashMap<String, LocalType>,
+    ) -> Option<FieldSchema> {
+        match expr {
+            syn::Expr::Reference(reference) => self.expr_schema(&reference.expr, locals),
+            syn::Expr::Paren(paren) => self.expr_schema(&paren.expr, locals),
+            syn::Expr::Struct(expr_struct) => {
+                let name: String = last_segment(&expr_struct.path)?;
+                self.structs
+                    .contains_key(&name)
+                    .then(|| self.struct_schema(&name, 0))
+            }
+            syn::Expr::Path(expr_path) => {
+                let name: String = last_segment(&expr_path.path)?;
+
+                match locals.get(&name)? {
+                    LocalType::Annotated(ty) => Some(self.type_schema(ty, 0)),
+                    LocalType::Struct(struct_name) => self
+                        .structs
+                        .contains_key(struct_name)
+                        .then(|| self.struct_schema(struct_name, 0)),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+enum LocalType {
+    Annotated(Box<syn::Type>),
+    Struct(String),
+}
+
+// Collects the arguments of `.json(...)` and `Json(...)` calls and the types of local bindings
+// in a handler.
+#[derive(Default)]
+struct JsonBodyFinder {
+    json_bodies: Vec<syn::Expr>,
+    locals: HashMap<String, LocalType>,
+}
+
+impl<'ast> Visit<'ast> for JsonBodyFinder {
+    fn visit_expr_method_call(&mut self, method_call: &'ast syn::ExprMethodCall) {
+        if method_call.method == "json" {
+            if let Some(argument) = method_call.args.first() {
+                self.json_bodies.push(argument.clone());
+            }
+        }
+
+        visit::visit_expr_method_call(self, method_call);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(func) = call.func.as_ref() {
+            if last_segment(&func.path).as_deref() == Some("Json") {
+                if let Some(argument) = call.args.first() {
+                    self.json_bodies.push(argument.clone());
+                }
+            }
+        }
+
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        let binding = match &local.pat {
+            syn::Pat::Type(pat_type) => match pat_type.pat.as_ref() {
+                syn::Pat::Ident(pat_ident) => Some((
+                    pat_ident.ident.to_string(),
+                    LocalType::Annotated(pat_type.ty.clone()),
+                )),
+                _ => None,
+            },
+            syn::Pat::Ident(pat_ident) => {
+                match local.init.as_ref().map(|init| init.expr.as_ref()) {
+                    Some(syn::Expr::Struct(expr_struct)) => last_segment(&expr_struct.path)
+                        .map(|name| (pat_ident.ident.to_string(), LocalType::Struct(name))),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        if let Some((name, local_type)) = binding {
+            self.locals.insert(name, local_type);
+        }
+
+        visit::visit_local(self, local);
+    }
+}
+
+pub fn extract_routes(code: &str) -> Result<ExtractedRoutes, syn::Error> {
+    let syntax: syn::File = syn::parse_file(code)?;
+
+    let mut index: CodeIndex = CodeIndex::default();
+    index.visit_file(&syntax);
+
+    let mut extracted: ExtractedRoutes = ExtractedRoutes {
+        routes: vec![],
+        unresolved: vec![],
+    };
+
+    let bindings: Vec<RouteBinding> = index
+        .bindings
+        .iter()
+        .map(|binding| RouteBinding {
+            route: join_route(
+                &index.mounted_prefix(binding.origin.as_ref()),
+                &binding.route,
+            ),
+            ..binding.clone()
+        })
+        .collect();
+
+    for binding in &bindings {
+        let is_duplicate: bool = extracted.routes.iter().any(|route_object| {
+            route_object.route == binding.route && route_object.method == binding.method
+        });
+
+        if is_duplicate {
+            continue;
+        }
+
+        let (request_body, response) = match index.handlers.get(&binding.handler) {
+            Some(handler) => (index.request_body(handler), index.response(handler)),
+            None => (None, BodyShape::Unresolved),
+        };
+
+        let response: Option<FieldSchema> = match response {
+            BodyShape::Resolved(response) => response,
+            BodyShape::Unresolved => {
+                extracted
+                    .unresolved
+                    .push((binding.route.clone(), binding.method));
+                None
+            }
+        };
+
+        extracted.routes.push(RouteObject {
+            is_route_dynamic: binding.route.contains('{'),
+            method: binding.method,
+            request_body,
+            response,
+            route: binding.route.clone(),
+        });
+    }
+
+    Ok(extracted)
+}
+
+// Fills the responses the static pass could not resolve from the LLM extracted routes.
+pub fn merge_fallback_routes(extracted: &mut ExtractedRoutes, fallback: &[RouteObject]) {
+    for (route, method) in extracted.unresolved.drain(..) {
+        let fallback_route: Option<&RouteObject> = fallback
+            .iter()
+            .find(|route_object| route_object.route == route && route_object.method == method);
+
+        let static_route: Option<&mut RouteObject> = extracted
+            .routes
+            .iter_mut()
+            .find(|route_object| route_object.route == route && route_object.method == method);
+
+        if let (Some(fallback_route), Some(static_route)) = (fallback_route, static_route) {
+            static_route.response = fallback_route.response.clone();
+
+            if static_route.request_body.is_none() {
+                static_route.request_body = fallback_route.request_body.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const BACKEND_CODE: &str = r#"
+use actix_web::{get, web, App, HttpResponse, HttpServer, Responder};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Task {
+    id: u64,
+    name: String,
+    completed: bool,
+    tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Price {
+    symbol: String,
+    price: f64,
+    change: Option<f64>,
+}
+
+async fn create_task(db: web::Data<AppState>, task: web::Json<Task>) -> impl Responder {
+    HttpResponse::Ok().finish()
+}
+
+async fn read_task(db: web::Data<AppState>, path: web::Path<u64>) -> impl Responder {
+    let task: Option<Task> = db.get(path.into_inner());
+    HttpResponse::Ok().json(task)
+}
+
+async fn list_tasks(db: web::Data<AppState>) -> web::Json<Vec<Task>> {
+    web::Json(db.all())
+}
+
+async fn crypto() -> impl Responder {
+    let body = fetch_prices().await;
+    HttpResponse::Ok().json(body)
+}
+
+#[get("/price")]
+async fn price() -> impl Responder {
+    HttpResponse::Ok().json(Price { symbol: "BTC".to_string(), price: 1.0 })
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    HttpServer::new(move || {
+        App::new()
+            .service(price)
+            .route("/task", web::post().to(create_task))
+            .route("/task/{id}", web::get().to(read_task))
+            .service(web::resource("/tasks").route(web::get().to(list_tasks)))
+            .route("/crypto", web::get().to(crypto))
+    })
+    .bind("127.0.0.1:8080")?
+    .run()
+    .await
+}
+"#;
+
+    fn route<'a>(extracted: &'a ExtractedRoutes, path: &str) -> &'a RouteObject {
+        extracted
+            .routes
+            .iter()
+            .find(|route_object| route_object.route == path)
+            .unwrap()
+    }
+
+    #[test]
+    fn tests_extract_routes() {
+        let extracted: ExtractedRoutes = extract_routes(BACKEND_CODE).unwrap();
+        let task =
+            json!({ "id": "number", "name": "string", "completed": "bool", "tags": ["string"] });
+
+        assert_eq!(extracted.routes.len(), 5);
+
+        let create: &RouteObject = route(&extracted, "/task");
+        assert_eq!(create.method, HttpMethod::Post);
+        assert_eq!(create.request_body, FieldSchema::from_value(&task));
+        assert_eq!(create.response, None);
+
+        let read: &RouteObject = route(&extracted, "/task/{id}");
+        assert!(read.is_route_dynamic);
+        // The handler serializes an Option<Task>, which is null when the task is missing.
+        assert_eq!(
+            read.response,
+            FieldSchema::from_value(&task).map(|task| FieldSchema::Optional(Box::new(task)))
+        );
+
+        let list: &RouteObject = route(&extracted, "/tasks");
+        assert_eq!(list.response, FieldSchema::from_value(&json!([task])));
+
+        let price: &RouteObject = route(&extracted, "/price");
+        let Some(FieldSchema::Object(price_fields)) = &price.response else {
+            panic!("The price response is not an object");
+        };
+        assert_eq!(
+            price_fields["change"],
+            FieldSchema::Optional(Box::new(FieldSchema::Field(FieldType::Float)))
+        );
+        assert_eq!(price_fields["price"], FieldSchema::Field(FieldType::Float));
+
+        assert_eq!(
+            extracted.unresolved,
+            vec![("/crypto".to_string(), HttpMethod::Get)]
+        );
+    }
+
+    #[test]
+    fn tests_merge_fallback_routes() {
+        let mut extracted: ExtractedRoutes = extract_routes(BACKEND_CODE).unwrap();
+        let prices = json!({ "bitcoin": "float" });
+
+        let fallback: Vec<RouteObject> = vec![RouteObject {
+            is_route_dynamic: false,
+            method: HttpMethod::Get,
+            request_body: None,
+            response: FieldSchema::from_value(&prices),
+            route: "/crypto".to_string(),
+        }];
+
+        merge_fallback_routes(&mut extracted, &fallback);
+
+        assert!(extracted.unresolved.is_empty());
+        assert_eq!(
+            route(&extracted, "/crypto").response,
+            FieldSchema::from_value(&prices)
+        );
+    }
+
+    #[test]
+    fn tests_merge_fallback_request_body() {
+        let mut extracted: ExtractedRoutes =
+            extract_routes("fn main() { App::new().route(\"/order\", web::post().to(order)); }")
+                .unwrap();
+        let order = json!({ "symbol": "string", "amount": "float" });
+
+        let fallback: Vec<RouteObject> = vec![RouteObject {
+            is_route_dynamic: false,
+            method: HttpMethod::Post,
+            request_body: FieldSchema::from_value(&order),
+            response: None,
+            route: "/order".to_string(),
+        }];
+
+        merge_fallback_routes(&mut extracted, &fallback);
+
+        assert_eq!(
+            route(&extracted, "/order").request_body,
+            FieldSchema::from_value(&order)
+        );
+    }
+
+    #[test]
+    fn tests_extract_scoped_routes() {
+        let actix: &str = r#"
+#[get("/health")]
+async fn health() -> impl Responder { HttpResponse::Ok().finish() }
+
+fn task_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/tasks", web::get().to(list_tasks));
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    HttpServer::new(|| {
+        App::new()
+            .route("/", web::get().to(index))
+            .service(
+                web::scope("/api")
+                    .service(health)
+                    .service(web::scope("/v1").configure(task_routes))
+                    .route("/items", web::post().to(create_item)),
+            )
+    })
+    .bind(("127.0.0.1", 8080))?
+    .run()
+    .await
+}
+"#;
+
+        let routes: Vec<String> = extract_routes(actix)
+            .unwrap()
+            .routes
+            .into_iter()
+            .map(|route_object| route_object.route)
+            .collect();
+
+        assert_eq!(
+            routes,
+            vec!["/api/health", "/api/v1/tasks", "/", "/api/items"]
+        );
+
+        let axum: &str = r#"
+fn task_routes() -> Router {
+    let router = Router::new().route("/tasks/:id", get(read_task));
+    router
+}
+
+#[tokio::main]
+async fn main() {
+    let admin = Router::new().route("/", get(admin_home));
+    let app = Router::new()
+        .route("/", get(index))
+        .nest("/api", Router::new().nest("/v1", task_routes()).route("/items", post(create_item)))
+        .nest("/admin", admin);
+}
+"#;
+
+        let routes: Vec<String> = extract_routes(axum)
+            .unwrap()
+            .routes
+            .into_iter()
+            .map(|route_object| route_object.route)
+            .collect();
+
+        assert_eq!(
+            routes,
+            vec!["/api/v1/tasks/:id", "/admin", "/", "/api/items"]
+        );
+    }
+}
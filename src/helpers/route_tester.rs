@@ -0,0 +1,450 @@
+use crate::models::agents::agent_traits::{FieldSchema, FieldType, HttpMethod, RouteObject};
+use reqwest::{Client, Method};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteTestResult {
+    pub route: String,
+    pub method: HttpMethod,
+    pub url: String,
+    pub status: Option<u16>,
+    pub passed: bool,
+    pub message: String,
+}
+
+// Values remembered per resource (e.g. "/item") so later routes can reuse the id a POST created.
+pub type ResourceValues = HashMap<String, HashMap<String, Value>>;
+
+pub fn sample_value(schema: &FieldSchema) -> Value {
+    match schema {
+        FieldSchema::Field(field_type) => match field_type {
+            FieldType::Number => json!(1),
+            FieldType::Float => json!(1.5),
+            FieldType::Bool => json!(true),
+            FieldType::List => json!([]),
+            FieldType::String | FieldType::Any => json!("example"),
+        },
+        FieldSchema::List(items) => json!(items
+            .first()
+            .map(sample_value)
+            .into_iter()
+            .collect::<Vec<Value>>()),
+        FieldSchema::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, field_schema)| (name.clone(), sample_value(field_schema)))
+                .collect(),
+        ),
+        FieldSchema::Optional(inner) => sample_value(inner),
+    }
+}
+
+// "/item/{id}" and "/item" both belong to the "/item" resource.
+pub fn resource_key(route: &str) -> String {
+    let static_segments: Vec<&str> = route
+        .split('/')
+        .take_while(|segment| !segment.starts_with('{'))
+        .collect();
+
+    let key: String = static_segments.join("/");
+    key.trim_end_matches('/').to_string()
+}
+
+fn method_rank(method: HttpMethod) -> u8 {
+    match method {
+        HttpMethod::Post => 0,
+        HttpMethod::Get | HttpMethod::Head | HttpMethod::Options => 1,
+        HttpMethod::Put | HttpMethod::Patch => 2,
+        HttpMethod::Delete => 3,
+    }
+}
+
+// Create -> read -> update -> delete within each resource, static routes before dynamic ones.
+pub fn order_routes(routes: &[RouteObject]) -> Vec<RouteObject> {
+    let mut ordered: Vec<RouteObject> = routes.to_vec();
+
+    ordered.sort_by_key(|route_object| {
+        (
+            resource_key(&route_object.route),
+            method_rank(route_object.method),
+            route_object.is_route_dynamic,
+        )
+    });
+
+    ordered
+}
+
+fn generated_parameter(name: &str) -> Value {
+    if name.to_lowercase().contains("id") {
+        json!(1)
+    } else {
+        json!("example")
+    }
+}
+
+pub fn fill_path(route: &str, known: Option<&HashMap<String, Value>>) -> String {
+    route
+        .split('/')
+        .map(|segment| {
+            match segment
+                .strip_prefix('{')
+                .and_then(|rest| rest.strip_suffix('}'))
+            {
+                Some(name) => {
+                    let value: Value = known
+                        .and_then(|values| values.get(name).or_else(|| values.get("id")))
+                        .cloned()
+                        .unwrap_or_else(|| generated_parameter(name));
+
+                    match value {
+                        Value::String(text) => text,
+                        other => other.to_string(),
+                    }
+                }
+                None => segment.to_string(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+pub fn build_request_body(
+    route_object: &RouteObject,
+    known: Option<&HashMap<String, Value>>,
+) -> Option<Value> {
+    let mut body: Value = sample_value(route_object.request_body.as_ref()?);
+
+    if let (Value::Object(fields), Some(known)) = (&mut body, known) {
+        for (name, value) in fields.iter_mut() {
+            if let Some(known_value) = known.get(name) {
+                *value = known_value.clone();
+            }
+        }
+    }
+
+    Some(body)
+}
+
+// Keeps scalar fields of what was sent and what came back, the response winning.
+pub fn remember_values(
+    values: &mut ResourceValues,
+    resource: &str,
+    sent: &Value,
+    received: &Value,
+) {
+    let entry: &mut HashMap<String, Value> = values.entry(resource.to_string()).or_default();
+
+    for source in [sent, received] {
+        if let Value::Object(fields) = source {
+            for (name, value) in fields {
+                if value.is_number() || value.is_string() {
+                    entry.insert(name.clone(), value.clone());
+                }
+            }
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "list",
+        Value::Object(_) => "object",
+    }
+}
+
+// "number" is accepted for floats too, since the LLM rarely tells the two apart. Extra fields are fine.
+pub fn validate_response(schema: &FieldSchema, value: &Value, path: &str) -> Vec<String> {
+    let mismatch = |expected: &str| {
+        vec![format!(
+            "{}: expected {}, got {}",
+            path,
+            expected,
+            type_name(value)
+        )]
+    };
+
+    match schema {
+        FieldSchema::Field(field_type) => {
+            let matches: bool = match field_type {
+                FieldType::Number | FieldType::Float => value.is_number(),
+                FieldType::String => value.is_string(),
+                FieldType::Bool => value.is_boolean(),
+                FieldType::List => value.is_array(),
+                FieldType::Any => true,
+            };
+
+            if matches {
+                vec![]
+            } else {
+                mismatch(&field_type.to_string())
+            }
+        }
+        FieldSchema::List(items) => match (value.as_array(), items.first()) {
+            (Some(elements), Some(item_schema)) => elements
+                .iter()
+                .enumerate()
+                .flat_map(|(index, element)| {
+                    validate_response(item_schema, element, &format!("{}[{}]", path, index))
+                })
+                .collect(),
+            (Some(_), None) => vec![],
+            (None, _) => mismatch("list"),
+        },
+        FieldSchema::Object(fields) => match value.as_object() {
+            Some(object) => fields
+                .iter()
+                .flat_map(|(name, field_schema)| {
+                    let field_path: String = format!("{}.{}", path, name);
+
+                    match (object.get(name), field_schema) {
+                        (Some(field_value), _) => {
+                            validate_response(field_schema, field_value, &field_path)
+                        }
+                        (None, FieldSchema::Optional(_)) => vec![],
+                        (None, _) => vec![format!("{}: missing field", field_path)],
+                    }
+                })
+                .collect(),
+            None => mismatch("object"),
+        },
+        FieldSchema::Optional(_) if value.is_null() => vec![],
+        FieldSchema::Optional(inner) => validate_response(inner, value, path),
+    }
+}
+
+pub fn to_reqwest_method(method: HttpMethod) -> Method {
+    match method {
+        HttpMethod::Get => Method::GET,
+        HttpMethod::Post => Method::POST,
+        HttpMethod::Put => Method::PUT,
+        HttpMethod::Patch => Method::PATCH,
+        HttpMethod::Delete => Method::DELETE,
+        HttpMethod::Head => Method::HEAD,
+        HttpMethod::Options => Method::OPTIONS,
+    }
+}
+
+pub async fn test_routes(
+    client: &Client,
+    base_url: &str,
+    routes: &[RouteObject],
+) -> Vec<RouteTestResult> {
+    let mut values: ResourceValues = HashMap::new();
+    let mut results: Vec<RouteTestResult> = vec![];
+
+    for route_object in order_routes(routes) {
+        let resource: String = resource_key(&route_object.route);
+        let known: Option<&HashMap<String, Value>> = values.get(&resource);
+
+        let url: String = format!("{}{}", base_url, fill_path(&route_object.route, known));
+        let body: Option<Value> = build_request_body(&route_object, known);
+
+        let mut request = client.request(to_reqwest_method(route_object.method), &url);
+
+        if let Some(body) = &body {
+            request = request.json(body);
+        }
+
+        let result: RouteTestResult = match request.send().await {
+            Ok(response) => {
+                let status: u16 = response.status().as_u16();
+                let mut passed: bool = response.status().is_success();
+                let mut message: String = format!("status {}", status);
+
+                let response_text: String = response.text().await.unwrap_or_default();
+                let parsed: Result<Value, serde_json::Error> = serde_json::from_str(&response_text);
+
+                if let (true, Some(schema)) = (passed, &route_object.response) {
+                    let mismatches: Vec<String> = match &parsed {
+                        Ok(received) => validate_response(schema, received, "response"),
+                        Err(e) => vec![format!("response is not JSON: {}", e)],
+                    };
+
+                    if !mismatches.is_empty() {
+                        passed = false;
+                        message = format!("{}, {}", message, mismatches.join("; "));
+                    }
+                }
+
+                let received: Value = parsed.unwrap_or(Value::Null);
+
+                if passed && route_object.method == HttpMethod::Post {
+                    remember_values(
+                        &mut values,
+                        &resource,
+                        &body.unwrap_or(Value::Null),
+                        &received,
+                    );
+                }
+
+                RouteTestResult {
+                    route: route_object.route.clone(),
+                    method: route_object.method,
+                    url,
+                    status: Some(status),
+                    passed,
+                    message,
+                }
+            }
+            Err(e) => RouteTestResult {
+                route: route_object.route.clone(),
+                method: route_object.method,
+                url,
+                status: None,
+                passed: false,
+                message: e.to_string(),
+            },
+        };
+
+        results.push(result);
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(path: &str, method: HttpMethod, request_body: Option<Value>) -> RouteObject {
+        RouteObject {
+            is_route_dynamic: path.contains('{'),
+            method,
+            request_body: request_body.as_ref().and_then(FieldSchema::from_value),
+            response: None,
+            route: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn tests_order_routes() {
+        let routes: Vec<RouteObject> = vec![
+            route("/item/{id}", HttpMethod::Delete, None),
+            route("/item/{id}", HttpMethod::Get, None),
+            route("/crypto", HttpMethod::Get, None),
+            route("/item/{id}", HttpMethod::Put, None),
+            route("/item", HttpMethod::Post, None),
+            route("/item", HttpMethod::Get, None),
+        ];
+
+        let ordered: Vec<(String, HttpMethod)> = order_routes(&routes)
+            .into_iter()
+            .map(|route_object| (route_object.route, route_object.method))
+            .collect();
+
+        assert_eq!(
+            ordered,
+            vec![
+                ("/crypto".to_string(), HttpMethod::Get),
+                ("/item".to_string(), HttpMethod::Post),
+                ("/item".to_string(), HttpMethod::Get),
+                ("/item/{id}".to_string(), HttpMethod::Get),
+                ("/item/{id}".to_string(), HttpMethod::Put),
+                ("/item/{id}".to_string(), HttpMethod::Delete),
+            ]
+        );
+    }
+
+    #[test]
+    fn tests_created_values_fill_later_routes() {
+        let create: RouteObject = route(
+            "/item",
+            HttpMethod::Post,
+            Some(json!({ "id": "number", "name": "string" })),
+        );
+        let update: RouteObject = route(
+            "/item/{id}",
+            HttpMethod::Put,
+            Some(json!({ "id": "number", "name": "string" })),
+        );
+
+        assert_eq!(fill_path("/item/{id}", None), "/item/1");
+        assert_eq!(fill_path("/price/{symbol}", None), "/price/example");
+
+        let sent: Value = build_request_body(&create, None).unwrap();
+        assert_eq!(sent, json!({ "id": 1, "name": "example" }));
+
+        let mut values: ResourceValues = HashMap::new();
+        remember_values(&mut values, "/item", &sent, &json!({ "id": 42 }));
+
+        let known: Option<&HashMap<String, Value>> = values.get("/item");
+        assert_eq!(fill_path("/item/{id}", known), "/item/42");
+        assert_eq!(
+            build_request_body(&update, known),
+            Some(json!({ "id": 42, "name": "example" }))
+        );
+    }
+
+    #[test]
+    fn tests_validate_response() {
+        let schema: FieldSchema = FieldSchema::from_value(&json!({
+            "id": "number",
+            "price": "number",
+            "name": "string",
+            "tags": ["string"],
+            "active": "bool"
+        }))
+        .unwrap();
+
+        let valid: Value = json!({
+            "id": 1,
+            "price": 9.5,
+            "name": "apple",
+            "tags": ["fruit"],
+            "active": true,
+            "extra": null
+        });
+        assert_eq!(
+            validate_response(&schema, &valid, "response"),
+            Vec::<String>::new()
+        );
+
+        let invalid: Value = json!({
+            "id": "1",
+            "price": 9.5,
+            "tags": ["fruit", 2],
+            "active": true
+        });
+        assert_eq!(
+            validate_response(&schema, &invalid, "response"),
+            vec![
+                "response.id: expected number, got string".to_string(),
+                "response.name: missing field".to_string(),
+                "response.tags[1]: expected string, got number".to_string(),
+            ]
+        );
+
+        let optional: FieldSchema = FieldSchema::Object(
+            [
+                ("id".to_string(), FieldSchema::Field(FieldType::Number)),
+                (
+                    "note".to_string(),
+                    FieldSchema::Optional(Box::new(FieldSchema::Field(FieldType::String))),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        for value in [
+            json!({ "id": 1, "note": null }),
+            json!({ "id": 1 }),
+            json!({ "id": 1, "note": "hi" }),
+        ] {
+            assert_eq!(
+                validate_response(&optional, &value, "response"),
+                Vec::<String>::new()
+            );
+        }
+
+        assert_eq!(
+            validate_response(&optional, &json!({ "id": 1, "note": 2 }), "response"),
+            vec!["response.note: expected string, got number".to_string()]
+        );
+    }
+}
@@ -0,0 +1,521 @@
+use crate::helpers::dependencies::cargo_home;
+use crate::helpers::server::ServerHandle;
+use dotenv::dotenv;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::str::FromStr;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use strum_macros::{Display, EnumString};
+
+#[cfg(unix)]
+use crate::helpers::relay::{PortRelay, RELAY_SOCKET, SERVE_RELAYED_COMMAND};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+pub const DEFAULT_CPU_SECONDS: u64 = 600;
+pub const DEFAULT_MEMORY_MB: u64 = 4096;
+pub const DEFAULT_TIMEOUT_SECS: u64 = 900;
+
+const BUBBLEWRAP: &str = "bwrap";
+const RELAY_MOUNT: &str = "/tmp/relay";
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Host directories the compiler, linker and generated code need at runtime.
+const SYSTEM_DIRS: [&str; 8] = [
+    "/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/etc", "/opt",
+];
+
+// Where builds, tests and the generated server run. Everything that differs between the two
+// goes through `Sandbox`, so the build and run helpers never branch on it themselves.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, EnumString)]
+pub enum SandboxKind {
+    #[strum(to_string = "none", serialize = "host", serialize = "disabled")]
+    Disabled,
+    #[strum(to_string = "bubblewrap", serialize = "bwrap")]
+    Bubblewrap,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SandboxLimits {
+    // CPU time of each process, so a busy loop in a build script or handler is killed.
+    pub cpu_seconds: u64,
+    pub memory_mb: u64,
+    // Wall time of each build, lint or test step.
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Sandbox {
+    pub kind: SandboxKind,
+    pub limits: SandboxLimits,
+}
+
+impl Sandbox {
+    // Bubblewrap needs Linux and the bwrap binary on PATH.
+    pub fn check(&self) -> Result<(), String> {
+        match self.kind {
+            SandboxKind::Disabled => Ok(()),
+            SandboxKind::Bubblewrap if bubblewrap_available() => Ok(()),
+            SandboxKind::Bubblewrap => Err(format!(
+                "The bubblewrap sandbox needs Linux with {} on PATH. Install bubblewrap, or set SANDBOX=none to run the generated code without isolation",
+                BUBBLEWRAP
+            )),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.kind {
+            SandboxKind::Disabled => {
+                "none, builds and the server run directly on this machine".to_string()
+            }
+            SandboxKind::Bubblewrap => format!(
+                "bubblewrap, read-only system and toolchain, writable project, loopback-only network, {}s CPU, {} MB memory, {}s per build step",
+                self.limits.cpu_seconds,
+                self.limits.memory_mb,
+                self.limits.timeout.as_secs()
+            ),
+        }
+    }
+
+    // Cargo can't reach the network from inside the sandbox.
+    pub fn isolates_network(&self) -> bool {
+        self.kind == SandboxKind::Bubblewrap
+    }
+
+    pub fn command(&self, project_dir: &Path, program: &str, args: &[&str]) -> Command {
+        match self.kind {
+            SandboxKind::Disabled => {
+                let mut command: Command = Command::new(program);
+                command.args(args).current_dir(project_dir);
+                command
+            }
+            SandboxKind::Bubblewrap => {
+                let project_dir: PathBuf = absolute(project_dir);
+
+                let mut command: Command = Command::new(BUBBLEWRAP);
+                command
+                    .args(bubblewrap_args(
+                        &project_dir,
+                        &project_dir,
+                        cargo_home().as_deref(),
+                        None,
+                    ))
+                    .arg(program)
+                    .args(args)
+                    .current_dir(&project_dir);
+
+                apply_limits(&mut command, &self.limits);
+                command
+            }
+        }
+    }
+
+    // Cargo is offline inside the sandbox, so the crates the manifest names are downloaded on
+    // the host first. Fetching only resolves and downloads, nothing of the project runs.
+    pub fn fetch_dependencies(&self, project_dir: &Path) -> io::Result<Option<Output>> {
+        if !self.isolates_network() {
+            return Ok(None);
+        }
+
+        let mut command: Command = Command::new("cargo");
+        command.arg("fetch").current_dir(project_dir);
+        output_with_timeout(command, self.limits.timeout).map(Some)
+    }
+
+    // Runs a build, lint or test step to completion. Sandboxed steps are killed once they run
+    // longer than the time limit.
+    pub fn output(&self, project_dir: &Path, program: &str, args: &[&str]) -> io::Result<Output> {
+        let mut command: Command = self.command(project_dir, program, args);
+
+        match self.kind {
+            SandboxKind::Disabled => command
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .output(),
+            SandboxKind::Bubblewrap => output_with_timeout(command, self.limits.timeout),
+        }
+    }
+
+    // Starts `cargo run` on `port` from `data_dir`, where the server reads and writes the data
+    // files it opens by relative path. A sandboxed server listens inside its own network
+    // namespace, so its port is relayed to the host's loopback through a unix socket.
+    pub fn spawn_server(
+        &self,
+        project_dir: &Path,
+        data_dir: &Path,
+        port: u16,
+    ) -> io::Result<ServerHandle> {
+        match self.kind {
+            SandboxKind::Disabled => {
+                let manifest: PathBuf = absolute(project_dir).join("Cargo.toml");
+                let mut command: Command = self.command(
+                    data_dir,
+                    "cargo",
+                    &["run", "--manifest-path", &manifest.to_string_lossy()],
+                );
+                command.env("PORT", port.to_string());
+                ServerHandle::spawn(command)
+            }
+            SandboxKind::Bubblewrap => {
+                self.spawn_relayed_server(&absolute(project_dir), &absolute(data_dir), port)
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    fn spawn_relayed_server(
+        &self,
+        project_dir: &Path,
+        data_dir: &Path,
+        port: u16,
+    ) -> io::Result<ServerHandle> {
+        let relay_dir: PathBuf =
+            env::temp_dir().join(format!("auto_gpt_relay_{}_{}", std::process::id(), port));
+        fs::create_dir_all(&relay_dir)?;
+
+        let relay: PortRelay = PortRelay::start(port, &relay_dir)?;
+        let relay_socket: String = format!("{}/{}", RELAY_MOUNT, RELAY_SOCKET);
+        let manifest: PathBuf = project_dir.join("Cargo.toml");
+
+        let mut command: Command = Command::new(BUBBLEWRAP);
+        command
+            .args(bubblewrap_args(
+                project_dir,
+                data_dir,
+                cargo_home().as_deref(),
+                Some(&relay_dir),
+            ))
+            .arg(env::current_exe()?)
+            .args([SERVE_RELAYED_COMMAND, &relay_socket, &port.to_string()])
+            .args(["cargo", "run", "--manifest-path"])
+            .arg(&manifest)
+            .env("PORT", port.to_string())
+            .current_dir(data_dir);
+
+        apply_limits(&mut command, &self.limits);
+
+        let mut server: ServerHandle = ServerHandle::spawn(command)?;
+        server.attach_relay(relay);
+        Ok(server)
+    }
+
+    #[cfg(not(unix))]
+    fn spawn_relayed_server(
+        &self,
+        _project_dir: &Path,
+        _data_dir: &Path,
+        _port: u16,
+    ) -> io::Result<ServerHandle> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "The bubblewrap sandbox needs Linux",
+        ))
+    }
+}
+
+// SANDBOX is "bubblewrap" (the default) or "none". Running generated code unisolated is an
+// explicit opt-out: without SANDBOX, a missing bubblewrap fails the check instead of quietly
+// running on the host.
+// SANDBOX_CPU_SECONDS, SANDBOX_MEMORY_MB and SANDBOX_TIMEOUT_SECS override the limits.
+pub fn selected_sandbox() -> Result<Sandbox, String> {
+    dotenv().ok();
+
+    let kind: SandboxKind = match env::var("SANDBOX") {
+        Ok(kind) => SandboxKind::from_str(kind.trim()).map_err(|_| {
+            format!(
+                "Unknown SANDBOX value {:?}. Use {} or {}",
+                kind,
+                SandboxKind::Bubblewrap,
+                SandboxKind::Disabled
+            )
+        })?,
+        Err(_) => SandboxKind::Bubblewrap,
+    };
+
+    let limit = |name: &str, default: u64| -> u64 {
+        env::var(name)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(default)
+    };
+
+    Ok(Sandbox {
+        kind,
+        limits: SandboxLimits {
+            cpu_seconds: limit("SANDBOX_CPU_SECONDS", DEFAULT_CPU_SECONDS),
+            memory_mb: limit("SANDBOX_MEMORY_MB", DEFAULT_MEMORY_MB),
+            timeout: Duration::from_secs(limit("SANDBOX_TIMEOUT_SECS", DEFAULT_TIMEOUT_SECS)),
+        },
+    })
+}
+
+fn bubblewrap_available() -> bool {
+    cfg!(target_os = "linux")
+        && env::var_os("PATH")
+            .map(|path| env::split_paths(&path).any(|dir| dir.join(BUBBLEWRAP).is_file()))
+            .unwrap_or(false)
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Only the system directories, the rust toolchain and the current executable are mounted
+// read-only, with fresh /dev, /proc and /tmp on top. The rest of the host, including $HOME with
+// its keys and this repository with its .env, stays invisible. Cargo's registry caches are
+// read-only too. Only the project and `work_dir` are writable, and the command starts in
+// `work_dir`. All namespaces are unshared, which leaves the network with
+// nothing but loopback.
+fn bubblewrap_args(
+    project_dir: &Path,
+    work_dir: &Path,
+    cargo_home: Option<&Path>,
+    relay_dir: Option<&Path>,
+) -> Vec<OsString> {
+    let mut args: Vec<OsString> = ["--die-with-parent", "--new-session", "--unshare-all"]
+        .iter()
+        .map(OsString::from)
+        .collect();
+
+    // A scratch $CARGO_HOME under the read-only mounts below, so cargo can still take its
+    // package cache lock.
+    if let Some(cargo_home) = cargo_home {
+        args.push("--tmpfs".into());
+        args.push(cargo_home.into());
+    }
+
+    let mut mount = |option: &str, source: &Path, target: &Path| {
+        args.push(option.into());
+        args.push(source.into());
+        args.push(target.into());
+    };
+
+    for dir in SYSTEM_DIRS {
+        let dir: &Path = Path::new(dir);
+
+        // Merged-usr systems link /bin and /lib into /usr.
+        match fs::read_link(dir) {
+            Ok(target) => mount("--symlink", &target, dir),
+            Err(_) => mount("--ro-bind-try", dir, dir),
+        }
+    }
+
+    if let Some(rustup_home) = rustup_home() {
+        mount("--ro-bind-try", &rustup_home, &rustup_home);
+    }
+
+    if let Some(cargo_home) = cargo_home {
+        for tool in ["bin", "config", "config.toml"] {
+            let path: PathBuf = cargo_home.join(tool);
+            mount("--ro-bind-try", &path, &path);
+        }
+    }
+
+    // Dependencies are fetched on the host before anything runs in here, so the caches are only
+    // read. A writable registry would let a build script change sources the host builds later.
+    if let Some(cargo_home) = cargo_home {
+        for cache in ["registry", "git"] {
+            let path: PathBuf = cargo_home.join(cache);
+            mount("--ro-bind-try", &path, &path);
+        }
+    }
+
+    if let Ok(current_exe) = env::current_exe() {
+        mount("--ro-bind", &current_exe, &current_exe);
+    }
+
+    for (option, dir) in [("--dev", "/dev"), ("--proc", "/proc"), ("--tmpfs", "/tmp")] {
+        args.push(option.into());
+        args.push(dir.into());
+    }
+
+    let mut bind = |source: &Path, target: &Path| {
+        args.push("--bind".into());
+        args.push(source.into());
+        args.push(target.into());
+    };
+
+    // The project's git history lives outside project_dir (see project_history), so nothing
+    // the sandbox writes here is run by the host's git.
+    bind(project_dir, project_dir);
+
+    if work_dir != project_dir {
+        bind(work_dir, work_dir);
+    }
+
+    if let Some(relay_dir) = relay_dir {
+        bind(relay_dir, Path::new(RELAY_MOUNT));
+    }
+
+    for arg in ["--setenv", "CARGO_NET_OFFLINE", "true", "--chdir"] {
+        args.push(arg.into());
+    }
+    args.push(work_dir.into());
+    args.push("--".into());
+
+    args
+}
+
+fn rustup_home() -> Option<PathBuf> {
+    env::var_os("RUSTUP_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".rustup")))
+}
+
+// Resource limits are set in the child before it execs and are inherited by everything it
+// starts, so they also hold for rustc, build scripts and the server.
+fn apply_limits(command: &mut Command, limits: &SandboxLimits) {
+    #[cfg(unix)]
+    {
+        let cpu_seconds: libc::rlim_t = limits.cpu_seconds as libc::rlim_t;
+        let memory_bytes: libc::rlim_t =
+            limits.memory_mb.saturating_mul(1024 * 1024) as libc::rlim_t;
+
+        unsafe {
+            // Only async-signal-safe calls between fork and exec.
+            command.pre_exec(move || {
+                let cpu: libc::rlimit = libc::rlimit {
+                    rlim_cur: cpu_seconds,
+                    rlim_max: cpu_seconds,
+                };
+                let memory: libc::rlimit = libc::rlimit {
+                    rlim_cur: memory_bytes,
+                    rlim_max: memory_bytes,
+                };
+
+                if libc::setrlimit(libc::RLIMIT_CPU, &cpu) != 0
+                    || libc::setrlimit(libc::RLIMIT_DATA, &memory) != 0
+                {
+                    return Err(io::Error::last_os_error());
+                }
+
+                Ok(())
+            });
+        }
+    }
+}
+
+fn collect<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer: Vec<u8> = vec![];
+
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+
+        buffer
+    })
+}
+
+// Like Command::output, but the whole process group is killed once `timeout` has passed.
+pub fn output_with_timeout(mut command: Command, timeout: Duration) -> io::Result<Output> {
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    #[cfg(unix)]
+    command.process_group(0);
+
+    let mut child: Child = command.spawn()?;
+    let stdout: JoinHandle<Vec<u8>> = collect(child.stdout.take());
+    let stderr: JoinHandle<Vec<u8>> = collect(child.stderr.take());
+
+    let deadline: Instant = Instant::now() + timeout;
+    let mut timed_out: bool = false;
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            #[cfg(unix)]
+            unsafe {
+                libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+            }
+
+            let _ = child.kill();
+            timed_out = true;
+            break child.wait()?;
+        }
+
+        thread::sleep(WAIT_POLL_INTERVAL);
+    };
+
+    let stdout: Vec<u8> = stdout.join().unwrap_or_default();
+    let mut stderr: Vec<u8> = stderr.join().unwrap_or_default();
+
+    if timed_out {
+        stderr.extend(format!("\nStopped after the {}s time limit\n", timeout.as_secs()).bytes());
+    }
+
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_bubblewrap_args() {
+        let project_dir: &Path = Path::new("/work/runs/demo/project");
+        let args: Vec<String> = bubblewrap_args(
+            project_dir,
+            Path::new("/work/runs/demo/fuzz_data"),
+            Some(Path::new("/cargo")),
+            Some(Path::new("/tmp/r1")),
+        )
+        .iter()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect();
+        let args: String = args.join(" ");
+
+        assert!(args.starts_with("--die-with-parent --new-session --unshare-all"));
+        assert!(args.contains("--ro-bind-try /usr /usr"));
+        assert!(!args.contains("--ro-bind / /"));
+        assert!(!args.contains(&format!(" {} ", env::var("HOME").unwrap_or_default())));
+        assert!(
+            args.contains("--tmpfs /tmp --bind /work/runs/demo/project /work/runs/demo/project")
+        );
+        assert!(args.contains("--bind /work/runs/demo/fuzz_data /work/runs/demo/fuzz_data"));
+        assert!(args.contains("--bind /tmp/r1 /tmp/relay"));
+        assert!(args.contains("--ro-bind-try /cargo/registry /cargo/registry"));
+        assert!(!args.contains("--bind /cargo"));
+        assert!(
+            args.ends_with("--setenv CARGO_NET_OFFLINE true --chdir /work/runs/demo/fuzz_data --")
+        );
+        assert!(!args.contains("--share-net"));
+
+        assert_eq!(SandboxKind::from_str("bwrap"), Ok(SandboxKind::Bubblewrap));
+        assert_eq!(SandboxKind::Disabled.to_string(), "none");
+        assert_eq!(SandboxKind::from_str("disabled"), Ok(SandboxKind::Disabled));
+    }
+
+    #[test]
+    fn tests_limits_and_timeout() {
+        let limits: SandboxLimits = SandboxLimits {
+            cpu_seconds: 30,
+            memory_mb: 512,
+            timeout: Duration::from_secs(1),
+        };
+
+        let mut command: Command = Command::new("sh");
+        command.args(["-c", "ulimit -t; ulimit -d; sleep 30"]);
+        apply_limits(&mut command, &limits);
+
+        let started: Instant = Instant::now();
+        let output: Output = output_with_timeout(command, limits.timeout).unwrap();
+
+        assert!(!output.status.success());
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "30\n524288\n");
+        assert!(String::from_utf8_lossy(&output.stderr).contains("1s time limit"));
+    }
+}
@@ -0,0 +1,319 @@
+use crate::helpers::general::active_run_workspace;
+#[cfg(unix)]
+use crate::helpers::relay::PortRelay;
+use crate::helpers::workspace::RunWorkspace;
+use reqwest::Client;
+use std::io::{BufRead, BufReader, Read};
+use std::net::TcpListener;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tokio::time;
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+pub const SERVER_READY_TIMEOUT: Duration = Duration::from_secs(60);
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// Output kept in memory for error messages and panic scans. The run log gets all of it.
+const MAX_SERVER_OUTPUT_BYTES: usize = 256 * 1024;
+
+// Owns the generated server process. Dropping the handle kills the whole process group,
+// so the binary started by `cargo run` goes down with cargo even when an agent panics.
+pub struct ServerHandle {
+    child: Child,
+    output: Arc<Mutex<String>>,
+    readers: Vec<JoinHandle<()>>,
+    stopped: bool,
+    #[cfg(unix)]
+    relay: Option<PortRelay>,
+}
+
+// Appends a line and drops whole lines from the front once the output is over `max_bytes`.
+fn push_capped(output: &mut String, line: &str, max_bytes: usize) {
+    output.push_str(line);
+    output.push('\n');
+
+    if output.len() > max_bytes {
+        let excess: usize = output.len() - max_bytes;
+        let cut: usize = output.as_bytes()[excess..]
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .map(|index| excess + index + 1)
+            .unwrap_or(output.len());
+
+        output.drain(..cut);
+    }
+}
+
+fn stream_lines<R: Read + Send + 'static>(
+    pipe: R,
+    output: Arc<Mutex<String>>,
+    run_workspace: Option<RunWorkspace>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            if let Some(run_workspace) = &run_workspace {
+                run_workspace.append_log(&format!("[server] {}", line));
+            }
+
+            if let Ok(mut output) = output.lock() {
+                push_capped(&mut output, &line, MAX_SERVER_OUTPUT_BYTES);
+            }
+        }
+    })
+}
+
+impl ServerHandle {
+    pub fn spawn(mut command: Command) -> std::io::Result<Self> {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        #[cfg(unix)]
+        command.process_group(0);
+
+        let mut child: Child = command.spawn()?;
+        let output: Arc<Mutex<String>> = Arc::new(Mutex::new(String::new()));
+        let run_workspace: Option<RunWorkspace> = active_run_workspace();
+        let mut readers: Vec<JoinHandle<()>> = vec![];
+
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(stream_lines(
+                stdout,
+                Arc::clone(&output),
+                run_workspace.clone(),
+            ));
+        }
+
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(stream_lines(stderr, Arc::clone(&output), run_workspace));
+        }
+
+        Ok(Self {
+            child,
+            output,
+            readers,
+            stopped: false,
+            #[cfg(unix)]
+            relay: None,
+        })
+    }
+
+    // The relay forwarding a sandboxed server's port lives as long as the server.
+    #[cfg(unix)]
+    pub fn attach_relay(&mut self, relay: PortRelay) {
+        self.relay = Some(relay);
+    }
+
+    pub fn output(&self) -> String {
+        self.output
+            .lock()
+            .map(|output| output.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn exit_status(&mut self) -> Option<ExitStatus> {
+        self.child.try_wait().ok().flatten()
+    }
+
+    // Any HTTP response, even a 404, means the server is accepting connections.
+    pub async fn wait_until_ready(
+        &mut self,
+        client: &Client,
+        url: &str,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let deadline: Instant = Instant::now() + timeout;
+
+        loop {
+            if let Some(status) = self.exit_status() {
+                self.join_readers();
+                return Err(format!(
+                    "Server exited early with {}:\n{}",
+                    status,
+                    self.output()
+                ));
+            }
+
+            if client.get(url).send().await.is_ok() {
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "Server was not ready after {} seconds:\n{}",
+                    timeout.as_secs(),
+                    self.output()
+                ));
+            }
+
+            time::sleep(READY_POLL_INTERVAL).await;
+        }
+    }
+
+    pub fn stop(&mut self) {
+        if self.stopped {
+            return;
+        }
+
+        #[cfg(unix)]
+        unsafe {
+            // The group id equals the child's pid because of process_group(0) above.
+            libc::killpg(self.child.id() as libc::pid_t, libc::SIGKILL);
+        }
+
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        self.join_readers();
+
+        #[cfg(unix)]
+        self.relay.take();
+
+        self.stopped = true;
+    }
+
+    fn join_readers(&mut self) {
+        for reader in self.readers.drain(..) {
+            let _ = reader.join();
+        }
+    }
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// Asks the OS for an unused port. It is released straight away, so a race is possible but unlikely.
+pub fn find_free_port() -> std::io::Result<u16> {
+    let listener: TcpListener = TcpListener::bind(("127.0.0.1", 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+fn parse_bind_address(argument: &str) -> Option<(String, u16)> {
+    let argument: &str = argument.trim();
+
+    if let Some(tuple) = argument.strip_prefix('(').and_then(|a| a.strip_suffix(')')) {
+        let (host, port) = tuple.split_once(',')?;
+        let host: &str = host.trim().strip_prefix('"')?.strip_suffix('"')?;
+        return Some((host.to_string(), port.trim().parse().ok()?));
+    }
+
+    let address: &str = argument.strip_prefix('"')?.strip_suffix('"')?;
+    let (host, port) = address.rsplit_once(':')?;
+    Some((host.to_string(), port.parse().ok()?))
+}
+
+// Generated servers must listen on $HOST:$PORT (falling back to their original address), which
+// lets every run pick its own free port and containers listen on every interface. Hardcoded
+// `host:port` arguments of `bind_call` (such as `.bind(`) are rewritten.
+pub fn rewrite_bind_calls(code: &str, bind_call: &str) -> String {
+    if code.contains("var(\"PORT\")") {
+        return code.to_string();
+    }
+
+    let mut converted: String = String::new();
+    let mut rest: &str = code;
+
+    while let Some(start) = rest.find(bind_call) {
+        let (before, after) = rest.split_at(start + bind_call.len());
+        converted.push_str(before);
+
+        let mut depth: usize = 1;
+        let end: Option<usize> = after.char_indices().find_map(|(index, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(index)
+        });
+
+        match end.and_then(|end| Some((end, parse_bind_address(&after[..end])?))) {
+            Some((end, (host, port))) => {
+                converted.push_str(&format!(
+                    "(std::env::var(\"HOST\").unwrap_or_else(|_| \"{}\".to_string()), std::env::var(\"PORT\").ok().and_then(|port| port.parse::<u16>().ok()).unwrap_or({}))",
+                    host, port
+                ));
+                rest = &after[end..];
+            }
+            None => rest = after,
+        }
+    }
+
+    converted.push_str(rest);
+    converted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_push_capped() {
+        let mut output: String = String::new();
+
+        for line in ["first line", "second", "third", "fourth"] {
+            push_capped(&mut output, line, 16);
+        }
+
+        assert_eq!(output, "third\nfourth\n");
+
+        push_capped(&mut output, "a line longer than the cap", 16);
+        assert_eq!(output, "");
+    }
+
+    #[tokio::test]
+    async fn tests_early_exit_reports_output() {
+        let mut command: Command = Command::new("sh");
+        command.args(["-c", "echo starting; echo 'bind failed' >&2; exit 3"]);
+
+        let mut server: ServerHandle = ServerHandle::spawn(command).unwrap();
+        let client: Client = Client::new();
+
+        let error: String = server
+            .wait_until_ready(&client, "http://127.0.0.1:9", Duration::from_secs(10))
+            .await
+            .unwrap_err();
+
+        assert!(error.starts_with("Server exited early"));
+        assert!(error.contains("starting"));
+        assert!(error.contains("bind failed"));
+    }
+
+    #[tokio::test]
+    async fn tests_stop_kills_process_group() {
+        let mut command: Command = Command::new("sh");
+        command.args(["-c", "sleep 30 & wait"]);
+
+        let mut server: ServerHandle = ServerHandle::spawn(command).unwrap();
+        let started: Instant = Instant::now();
+        server.stop();
+
+        assert!(server.exit_status().is_some());
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn tests_rewrite_bind_calls() {
+        let expected: &str = r#"HttpServer::new(|| App::new()).bind((std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string()), std::env::var("PORT").ok().and_then(|port| port.parse::<u16>().ok()).unwrap_or(8080)))?.run().await"#;
+
+        let literal: &str = r#"HttpServer::new(|| App::new()).bind("127.0.0.1:8080")?.run().await"#;
+        let tuple: &str =
+            r#"HttpServer::new(|| App::new()).bind(("127.0.0.1", 8080))?.run().await"#;
+
+        assert_eq!(rewrite_bind_calls(literal, ".bind("), expected);
+        assert_eq!(rewrite_bind_calls(tuple, ".bind("), expected);
+        assert_eq!(rewrite_bind_calls(expected, ".bind("), expected);
+
+        let dynamic: &str = "server.bind(address)?";
+        assert_eq!(rewrite_bind_calls(dynamic, ".bind("), dynamic);
+    }
+
+    #[test]
+    fn tests_find_free_port() {
+        let port: u16 = find_free_port().unwrap();
+        assert!(TcpListener::bind(("127.0.0.1", port)).is_ok());
+    }
+}
@@ -0,0 +1,304 @@
+use crate::helpers::dependencies::manifest_dependencies;
+use crate::helpers::framework::{target_framework, Framework};
+use dotenv::dotenv;
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The backend agent starts from this file, kept next to main.rs like in the original
+// actix_template checkout.
+pub const CODE_TEMPLATE_FILE: &str = "src/template.rs";
+
+const SKIPPED_DIRECTORIES: [&str; 2] = ["target", ".git"];
+
+pub struct BuiltinTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub framework: Framework,
+    pub files: &'static [(&'static str, &'static str)],
+}
+
+pub const BUILTIN_TEMPLATES: [BuiltinTemplate; 3] = [
+    BuiltinTemplate {
+        name: "actix-json-db",
+        description: "actix-web server storing its data in a JSON file",
+        framework: Framework::ActixWeb,
+        files: &[
+            (
+                "Cargo.toml",
+                include_str!("../../templates/actix_json_db/Cargo.toml"),
+            ),
+            (
+                "src/main.rs",
+                include_str!("../../templates/actix_json_db/src/main.rs"),
+            ),
+        ],
+    },
+    BuiltinTemplate {
+        name: "actix-sqlite",
+        description: "actix-web server storing its data in SQLite through rusqlite",
+        framework: Framework::ActixWeb,
+        files: &[
+            (
+                "Cargo.toml",
+                include_str!("../../templates/actix_sqlite/Cargo.toml"),
+            ),
+            (
+                "src/main.rs",
+                include_str!("../../templates/actix_sqlite/src/main.rs"),
+            ),
+        ],
+    },
+    BuiltinTemplate {
+        name: "axum-minimal",
+        description: "axum server keeping its data in memory",
+        framework: Framework::Axum,
+        files: &[
+            (
+                "Cargo.toml",
+                include_str!("../../templates/axum_minimal/Cargo.toml"),
+            ),
+            (
+                "src/main.rs",
+                include_str!("../../templates/axum_minimal/src/main.rs"),
+            ),
+        ],
+    },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateSource {
+    Builtin(&'static str),
+    Directory(PathBuf),
+}
+
+impl TemplateSource {
+    // A built-in template name, or else the path of a user-supplied template directory.
+    pub fn resolve(name_or_path: &str) -> Result<Self, String> {
+        let name_or_path: &str = name_or_path.trim();
+
+        if let Some(template) = BUILTIN_TEMPLATES
+            .iter()
+            .find(|template| template.name == name_or_path)
+        {
+            return Ok(Self::Builtin(template.name));
+        }
+
+        let directory: &Path = Path::new(name_or_path);
+
+        if !directory.is_dir() {
+            let names: Vec<&str> = BUILTIN_TEMPLATES.iter().map(|t| t.name).collect();
+            return Err(format!(
+                "Template {} is neither a built-in template ({}) nor a directory",
+                name_or_path,
+                names.join(", ")
+            ));
+        }
+
+        let has_code: bool =
+            directory.join("src/main.rs").is_file() || directory.join(CODE_TEMPLATE_FILE).is_file();
+
+        if !directory.join("Cargo.toml").is_file() || !has_code {
+            return Err(format!(
+                "Template directory {} needs a Cargo.toml and src/main.rs",
+                name_or_path
+            ));
+        }
+
+        Ok(Self::Directory(directory.to_path_buf()))
+    }
+
+    // The framework the template is written for. Directory templates are recognized by the
+    // framework crate in their Cargo.toml, or None when it has neither.
+    pub fn framework(&self) -> Option<Framework> {
+        match self {
+            Self::Builtin(name) => BUILTIN_TEMPLATES
+                .iter()
+                .find(|template| template.name == *name)
+                .map(|template| template.framework),
+            Self::Directory(directory) => {
+                let cargo_toml: String = fs::read_to_string(directory.join("Cargo.toml")).ok()?;
+                let dependencies: BTreeSet<String> = manifest_dependencies(&cargo_toml);
+
+                if dependencies.contains("actix_web") {
+                    Some(Framework::ActixWeb)
+                } else if dependencies.contains("axum") {
+                    Some(Framework::Axum)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub fn check_framework(&self, framework: Framework) -> Result<(), String> {
+        match self.framework() {
+            Some(template_framework) if template_framework != framework => Err(format!(
+                "PROJECT_TEMPLATE is written for {} but TARGET_FRAMEWORK is {}",
+                template_framework, framework
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // Copies the template into `output`, which must not exist yet. Templates without their own
+    // src/template.rs get a copy of src/main.rs, so every project has a code template.
+    pub fn materialize(&self, output: &Path) -> Result<(), String> {
+        if output.exists() {
+            return Err(format!(
+                "Output directory {} already exists",
+                output.display()
+            ));
+        }
+
+        let written: std::io::Result<()> = match self {
+            Self::Builtin(name) => write_builtin(name, output),
+            Self::Directory(directory) => copy_directory(directory, output),
+        };
+
+        written.map_err(|e| format!("Couldn't write the template: {}", e))?;
+
+        let code_template: PathBuf = output.join(CODE_TEMPLATE_FILE);
+
+        if !code_template.exists() {
+            fs::copy(output.join("src/main.rs"), code_template)
+                .map_err(|e| format!("Couldn't create the code template: {}", e))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_builtin(name: &str, output: &Path) -> std::io::Result<()> {
+    let template: &BuiltinTemplate = BUILTIN_TEMPLATES
+        .iter()
+        .find(|template| template.name == name)
+        .expect("Built-in template names are resolved before materializing.");
+
+    for (path, contents) in template.files {
+        let target: PathBuf = output.join(path);
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(target, contents)?;
+    }
+
+    Ok(())
+}
+
+fn copy_directory(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+
+    for entry in fs::read_dir(source)? {
+        let entry: fs::DirEntry = entry?;
+        let path: PathBuf = entry.path();
+        let name = entry.file_name();
+
+        if path.is_dir() {
+            if SKIPPED_DIRECTORIES.iter().any(|skipped| name == *skipped) {
+                continue;
+            }
+            copy_directory(&path, &target.join(&name))?;
+        } else {
+            fs::copy(&path, target.join(&name))?;
+        }
+    }
+
+    Ok(())
+}
+
+// PROJECT_TEMPLATE picks a built-in template by name or points at a template directory.
+// Without it, the target framework's own template is used.
+pub fn selected_template() -> String {
+    dotenv().ok();
+    env::var("PROJECT_TEMPLATE")
+        .unwrap_or_else(|_| target_framework().default_template().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("templates_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn tests_materialize_builtin() {
+        let output: PathBuf = temp_dir("builtin");
+
+        for template in &BUILTIN_TEMPLATES {
+            let project: PathBuf = output.join(template.name);
+            TemplateSource::resolve(template.name)
+                .unwrap()
+                .materialize(&project)
+                .unwrap();
+
+            let main: String = fs::read_to_string(project.join("src/main.rs")).unwrap();
+            assert!(main.contains("var(\"PORT\")") && main.contains("var(\"HOST\")"));
+            assert_eq!(
+                fs::read_to_string(project.join(CODE_TEMPLATE_FILE)).unwrap(),
+                main
+            );
+            assert!(project.join("Cargo.toml").is_file());
+        }
+
+        let existing: Result<(), String> =
+            TemplateSource::Builtin("axum-minimal").materialize(&output.join("axum-minimal"));
+        assert!(existing.is_err());
+
+        fs::remove_dir_all(output).unwrap();
+    }
+
+    #[test]
+    fn tests_materialize_directory() {
+        let source: PathBuf = temp_dir("source");
+        let output: PathBuf = temp_dir("copy");
+
+        fs::create_dir_all(source.join("src")).unwrap();
+        fs::create_dir_all(source.join("target/debug")).unwrap();
+        fs::write(source.join("Cargo.toml"), "[package]\nname = \"custom\"\n").unwrap();
+        fs::write(source.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(source.join(CODE_TEMPLATE_FILE), "// template\n").unwrap();
+
+        let template: TemplateSource = TemplateSource::resolve(source.to_str().unwrap()).unwrap();
+        template.materialize(&output).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(output.join(CODE_TEMPLATE_FILE)).unwrap(),
+            "// template\n"
+        );
+        assert!(!output.join("target").exists());
+
+        assert!(TemplateSource::resolve("no-such-template").is_err());
+        assert!(TemplateSource::resolve(output.join("src").to_str().unwrap()).is_err());
+
+        fs::remove_dir_all(source).unwrap();
+        fs::remove_dir_all(output).unwrap();
+    }
+
+    #[test]
+    fn tests_check_framework() {
+        let axum: TemplateSource = TemplateSource::resolve("axum-minimal").unwrap();
+        assert!(axum.check_framework(Framework::Axum).is_ok());
+        assert!(axum.check_framework(Framework::ActixWeb).is_err());
+
+        let source: PathBuf = temp_dir("framework");
+        fs::create_dir_all(source.join("src")).unwrap();
+        fs::write(source.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(
+            source.join("Cargo.toml"),
+            "[package]\nname = \"custom\"\n\n[dependencies]\nactix-web = \"4\"\n",
+        )
+        .unwrap();
+
+        let template: TemplateSource = TemplateSource::resolve(source.to_str().unwrap()).unwrap();
+        assert_eq!(template.framework(), Some(Framework::ActixWeb));
+        assert!(template.check_framework(Framework::Axum).is_err());
+
+        fs::remove_dir_all(source).unwrap();
+    }
+}
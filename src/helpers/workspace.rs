@@ -0,0 +1,275 @@
+use crate::helpers::command_line::PrintCommand;
+use crate::helpers::framework::{selected_framework, Framework};
+use crate::helpers::project_history::init_history;
+use crate::helpers::templates::{selected_template, TemplateSource};
+use dotenv::dotenv;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_RUNS_DIR: &str = "runs";
+pub const DEFAULT_KEPT_RUNS: usize = 10;
+
+const LATEST_LINK: &str = "latest";
+const MAX_SLUG_LENGTH: usize = 40;
+
+// Everything one ManagingAgent run produces, under runs/<timestamp>-<slug>/:
+//   project/          the generated project
+//   project.git/      the project's history, kept out of the sandbox's reach
+//   fuzz_data/        copies of the project's data files for the fuzzed server
+//   factsheet.json    the fact sheet after each agent
+//   api_schema.json   the extracted API endpoints
+//   patch_history.json
+//   run.log           every agent message
+//   report.md         the summary written when the run ends
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunWorkspace {
+    pub root: PathBuf,
+}
+
+impl RunWorkspace {
+    pub fn project_dir(&self) -> PathBuf {
+        self.root.join("project")
+    }
+
+    pub fn fuzz_data_dir(&self) -> PathBuf {
+        self.root.join("fuzz_data")
+    }
+
+    pub fn factsheet_path(&self) -> PathBuf {
+        self.root.join("factsheet.json")
+    }
+
+    pub fn api_schema_path(&self) -> PathBuf {
+        self.root.join("api_schema.json")
+    }
+
+    pub fn patch_history_path(&self) -> PathBuf {
+        self.root.join("patch_history.json")
+    }
+
+    pub fn log_path(&self) -> PathBuf {
+        self.root.join("run.log")
+    }
+
+    pub fn report_path(&self) -> PathBuf {
+        self.root.join("report.md")
+    }
+
+    // Creates a new run directory. Two runs starting in the same second get a numbered suffix.
+    pub fn create(runs_dir: &Path, description: &str, unix_secs: u64) -> std::io::Result<Self> {
+        fs::create_dir_all(runs_dir)?;
+
+        let name: String = format!("{}-{}", format_timestamp(unix_secs), slugify(description));
+        let mut attempt: usize = 1;
+
+        loop {
+            let root: PathBuf = if attempt == 1 {
+                runs_dir.join(&name)
+            } else {
+                runs_dir.join(format!("{}-{}", name, attempt))
+            };
+
+            match fs::create_dir(&root) {
+                Ok(()) => return Ok(Self { root }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn append_log(&self, line: &str) {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.log_path());
+
+        if let Ok(mut file) = file {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+pub fn runs_dir() -> PathBuf {
+    dotenv().ok();
+    PathBuf::from(env::var("RUNS_DIR").unwrap_or_else(|_| DEFAULT_RUNS_DIR.to_string()))
+}
+
+pub fn slugify(description: &str) -> String {
+    let mut slug: String = String::new();
+
+    for c in description.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+
+        if slug.len() >= MAX_SLUG_LENGTH {
+            break;
+        }
+    }
+
+    let slug: &str = slug.trim_end_matches('-');
+
+    if slug.is_empty() {
+        "run".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+// UTC as YYYYMMDD-HHMMSS, so run directories sort by start time.
+pub fn format_timestamp(unix_secs: u64) -> String {
+    let days: i64 = (unix_secs / 86_400) as i64;
+    let seconds_of_day: u64 = unix_secs % 86_400;
+
+    // Days to civil date, from Howard Hinnant's date algorithms.
+    let z: i64 = days + 719_468;
+    let era: i64 = z.div_euclid(146_097);
+    let day_of_era: i64 = z - era * 146_097;
+    let year_of_era: i64 =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index: i64 = (5 * day_of_year + 2) / 153;
+    let day: i64 = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month: i64 = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year: i64 = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+// Points runs/latest at the given run. The link is replaced by a rename, so readers never
+// see it missing.
+pub fn update_latest_link(runs_dir: &Path, run_root: &Path) -> std::io::Result<()> {
+    let target: &std::ffi::OsStr = run_root
+        .file_name()
+        .ok_or_else(|| std::io::Error::other("Run directory has no name"))?;
+
+    let staging: PathBuf = runs_dir.join(format!(".{}.{}", LATEST_LINK, std::process::id()));
+    let _ = fs::remove_file(&staging);
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(target, &staging)?;
+
+    #[cfg(not(unix))]
+    fs::write(&staging, target.to_string_lossy().as_bytes())?;
+
+    fs::rename(staging, runs_dir.join(LATEST_LINK))
+}
+
+// Creates the run directory, materializes the selected template into it and marks it latest.
+// The project gets its own git history. Without git the run still works, just without it.
+// An unknown framework or a template written for another framework stops the run here.
+pub fn start_run(description: &str) -> Result<RunWorkspace, String> {
+    let framework: Framework = selected_framework()?;
+    let template: String = selected_template();
+    let source: TemplateSource = TemplateSource::resolve(&template)?;
+    source.check_framework(framework)?;
+    let runs_dir: PathBuf = runs_dir();
+
+    let unix_secs: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+
+    let workspace: RunWorkspace = RunWorkspace::create(&runs_dir, description, unix_secs)
+        .map_err(|e| format!("Couldn't create the run directory: {}", e))?;
+
+    source.materialize(&workspace.project_dir())?;
+
+    if let Err(e) = init_history(&workspace.project_dir(), template.trim()) {
+        PrintCommand::Issue.print_agent_msg("Project history", &e);
+    }
+
+    update_latest_link(&runs_dir, &workspace.root)
+        .map_err(|e| format!("Couldn't update the latest run link: {}", e))?;
+
+    Ok(workspace)
+}
+
+// Removes all but the `keep` newest runs. The run `latest` points at is always kept.
+pub fn cleanup_runs(runs_dir: &Path, keep: usize) -> std::io::Result<Vec<PathBuf>> {
+    let latest: Option<PathBuf> = fs::read_link(runs_dir.join(LATEST_LINK)).ok();
+
+    let mut runs: Vec<PathBuf> = fs::read_dir(runs_dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().map(|kind| kind.is_dir()).unwrap_or(false))
+        .map(|entry| entry.path())
+        .collect();
+
+    runs.sort();
+
+    let removable: usize = runs.len().saturating_sub(keep);
+    let mut removed: Vec<PathBuf> = vec![];
+
+    for run in runs.into_iter().take(removable) {
+        let is_latest: bool = latest
+            .as_deref()
+            .is_some_and(|latest| run.file_name() == Some(latest.as_os_str()));
+
+        if is_latest {
+            continue;
+        }
+
+        fs::remove_dir_all(&run)?;
+        removed.push(run);
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_run_names() {
+        assert_eq!(format_timestamp(0), "19700101-000000");
+        assert_eq!(format_timestamp(1_709_210_096), "20240229-123456");
+        assert_eq!(
+            slugify("Need a full stack app that tracks my fitness progress!"),
+            "need-a-full-stack-app-that-tracks-my-fit"
+        );
+        assert_eq!(slugify("  ?? "), "run");
+    }
+
+    #[test]
+    fn tests_create_and_cleanup_runs() {
+        let runs_dir: PathBuf = env::temp_dir().join(format!("runs_{}", std::process::id()));
+
+        let first: RunWorkspace = RunWorkspace::create(&runs_dir, "todo api", 100).unwrap();
+        let second: RunWorkspace = RunWorkspace::create(&runs_dir, "todo api", 100).unwrap();
+        let third: RunWorkspace = RunWorkspace::create(&runs_dir, "crypto", 200).unwrap();
+
+        assert!(first.root.ends_with("19700101-000140-todo-api"));
+        assert!(second.root.ends_with("19700101-000140-todo-api-2"));
+
+        update_latest_link(&runs_dir, &first.root).unwrap();
+        assert_eq!(
+            fs::read_link(runs_dir.join(LATEST_LINK)).unwrap(),
+            Path::new("19700101-000140-todo-api")
+        );
+
+        let removed: Vec<PathBuf> = cleanup_runs(&runs_dir, 1).unwrap();
+
+        assert_eq!(removed, vec![second.root]);
+        assert!(first.root.exists() && third.root.exists());
+
+        fs::remove_dir_all(runs_dir).unwrap();
+    }
+}
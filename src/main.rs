@@ -13,7 +13,36 @@ mod helpers;
 mod models;
 
 use helpers::command_line::get_user_response;
+use helpers::workspace::{cleanup_runs, runs_dir, DEFAULT_KEPT_RUNS};
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Started by the bubblewrap sandbox to relay the generated server's port to the host.
+    #[cfg(unix)]
+    if args.first().map(String::as_str) == Some(helpers::relay::SERVE_RELAYED_COMMAND) {
+        std::process::exit(helpers::relay::serve_relayed(&args[1..]));
+    }
+
+    // `cleanup [keep]` removes old run directories instead of starting a run.
+    if args.first().map(String::as_str) == Some("cleanup") {
+        let keep: usize = args
+            .get(1)
+            .and_then(|keep| keep.parse().ok())
+            .unwrap_or(DEFAULT_KEPT_RUNS);
+
+        match cleanup_runs(&runs_dir(), keep) {
+            Ok(removed) => {
+                for run in &removed {
+                    println!("Removed {}", run.display());
+                }
+                println!("Removed {} runs, kept the newest {}", removed.len(), keep);
+            }
+            Err(e) => eprintln!("Couldn't clean up the runs: {}", e),
+        }
+
+        return;
+    }
+
     let user_req = get_user_response("What webserver are we building today?");
 }
@@ -152,8 +152,14 @@ mod tests {
             project_description: "Build a full stack website with user login and logout that shows latest forex prices".to_string(),
             project_scope: None,
             external_urls: None,
+            data_model: None,
             backend_code: None,
-            api_endpoint_schema: None
+            audit_findings: None,
+            api_endpoint_schema: None,
+            frontend_code: None,
+            backend_failure: None,
+            remaining_warnings: None,
+            backend_base_url: None
         };
 
         agent
@@ -0,0 +1,323 @@
+use crate::ai_functions::aifunc_auth::print_auth_backend_code;
+use crate::helpers::command_line::PrintCommand;
+use crate::helpers::framework::project_context;
+use crate::helpers::general::{ai_task_request, run_backend_project, save_backend_code};
+use crate::helpers::server::{find_free_port, ServerHandle, SERVER_READY_TIMEOUT};
+use crate::models::agents::agent_backend::{AgentBackendDeveloper, BugStage};
+use crate::models::agents::agent_traits::{
+    FactSheet, FieldSchema, HttpMethod, RouteObject, SpecialFunctions,
+};
+use crate::models::agents_basic::basic_agents::{AgentState, BasicAgent};
+
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug)]
+pub struct AgentAuthDeveloper {
+    attributes: BasicAgent,
+    // The auth changes go through the backend developer's checks and fixes, so they share its
+    // edit blocks, patch history and bug limits.
+    backend_developer: AgentBackendDeveloper,
+}
+
+impl AgentAuthDeveloper {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Adds user signup, login and logout to the backend server".to_string(),
+            position: "Authentication developer".to_string(),
+            state: AgentState::Discovery,
+            memory: vec![],
+        };
+
+        // The generic endpoint tests call /logout without a token and count the 401 the auth
+        // spec requires as a failure, so check_login is the only test of these routes.
+        let mut backend_developer: AgentBackendDeveloper = AgentBackendDeveloper::new();
+        backend_developer.skip_route_tests(auth_route_objects());
+
+        Self {
+            attributes,
+            backend_developer,
+        }
+    }
+
+    async fn call_auth_backend_code(&mut self, factsheet: &mut FactSheet) {
+        let msg_context: String = format!(
+            "BACKEND_CODE: {:?} \n {}",
+            factsheet.backend_code,
+            project_context()
+        );
+
+        let ai_response: String = ai_task_request(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_auth_backend_code),
+            print_auth_backend_code,
+        )
+        .await;
+
+        save_backend_code(
+            &ai_response,
+            &self.attributes.position,
+            get_function_string!(print_auth_backend_code),
+        );
+        factsheet.backend_code = Some(ai_response);
+    }
+
+    // Runs the login flow against the code that already passed the backend checks.
+    async fn check_login(&self) -> Result<(), String> {
+        PrintCommand::UnitTest.print_agent_msg(
+            self.attributes.position.as_str(),
+            "Authentication unit testing: testing the login flow",
+        );
+
+        let client: Client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        let port: u16 = find_free_port().map_err(|e| e.to_string())?;
+        let base_url: String = format!("http://localhost:{}", port);
+        let mut run_backend_server: ServerHandle = run_backend_project(port);
+
+        run_backend_server
+            .wait_until_ready(&client, &base_url, SERVER_READY_TIMEOUT)
+            .await?;
+
+        let login_result: Result<(), String> = check_login_flow(&client, &base_url).await;
+        run_backend_server.stop();
+
+        login_result
+    }
+}
+
+fn auth_route_objects() -> Vec<RouteObject> {
+    let credentials: Option<FieldSchema> =
+        FieldSchema::from_value(&json!({ "username": "string", "password": "string" }));
+
+    vec![
+        RouteObject {
+            is_route_dynamic: false,
+            method: HttpMethod::Post,
+            request_body: credentials.clone(),
+            response: None,
+            route: "/signup".to_string(),
+        },
+        RouteObject {
+            is_route_dynamic: false,
+            method: HttpMethod::Post,
+            request_body: credentials,
+            response: FieldSchema::from_value(&json!({ "token": "string" })),
+            route: "/login".to_string(),
+        },
+        RouteObject {
+            is_route_dynamic: false,
+            method: HttpMethod::Post,
+            request_body: None,
+            response: None,
+            route: "/logout".to_string(),
+        },
+    ]
+}
+
+fn register_auth_routes(api_endpoints: &mut Vec<RouteObject>) {
+    for auth_route in auth_route_objects() {
+        let is_registered: bool = api_endpoints.iter().any(|route_object| {
+            route_object.route == auth_route.route && route_object.method == auth_route.method
+        });
+
+        if !is_registered {
+            api_endpoints.push(auth_route);
+        }
+    }
+}
+
+fn expect_status(step: &str, expected: StatusCode, actual: StatusCode) -> Result<(), String> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}: expected status {} got {}",
+            step, expected, actual
+        ))
+    }
+}
+
+// Runs signup -> bad login -> login -> logout -> logout again against a running server.
+async fn check_login_flow(client: &Client, base_url: &str) -> Result<(), String> {
+    let timestamp: u128 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+
+    let username: String = format!("auth_test_{}", timestamp);
+    let credentials = json!({ "username": username, "password": "Password123!" });
+    let wrong_credentials = json!({ "username": username, "password": "WrongPassword" });
+
+    let send_err = |step: &str, e: reqwest::Error| format!("{}: {}", step, e);
+
+    let signup = client
+        .post(format!("{}/signup", base_url))
+        .json(&credentials)
+        .send()
+        .await
+        .map_err(|e| send_err("signup", e))?;
+    expect_status("signup", StatusCode::OK, signup.status())?;
+
+    let bad_login = client
+        .post(format!("{}/login", base_url))
+        .json(&wrong_credentials)
+        .send()
+        .await
+        .map_err(|e| send_err("login with wrong password", e))?;
+    expect_status(
+        "login with wrong password",
+        StatusCode::UNAUTHORIZED,
+        bad_login.status(),
+    )?;
+
+    let login = client
+        .post(format!("{}/login", base_url))
+        .json(&credentials)
+        .send()
+        .await
+        .map_err(|e| send_err("login", e))?;
+    expect_status("login", StatusCode::OK, login.status())?;
+
+    let login_body: serde_json::Value = login
+        .json()
+        .await
+        .map_err(|e| send_err("login response", e))?;
+
+    let token: String = login_body["token"]
+        .as_str()
+        .ok_or("login: response has no token")?
+        .to_string();
+
+    let logout = client
+        .post(format!("{}/logout", base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .map_err(|e| send_err("logout", e))?;
+    expect_status("logout", StatusCode::OK, logout.status())?;
+
+    let second_logout = client
+        .post(format!("{}/logout", base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .map_err(|e| send_err("logout with expired token", e))?;
+    expect_status(
+        "logout with expired token",
+        StatusCode::UNAUTHORIZED,
+        second_logout.status(),
+    )?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentAuthDeveloper {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let is_user_login_and_logout: bool = factsheet
+            .project_scope
+            .map(|project_scope| project_scope.is_user_login_and_logout)
+            .unwrap_or(false);
+
+        if !is_user_login_and_logout {
+            self.attributes.state = AgentState::Finishing;
+        }
+
+        while self.attributes.state != AgentState::Finishing {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    if let Some(backend_code) = &factsheet.backend_code {
+                        self.backend_developer.set_tested_code(backend_code);
+                    }
+
+                    self.call_auth_backend_code(factsheet).await;
+                    self.attributes.state = AgentState::UnitTesting;
+                }
+                AgentState::Working => {
+                    self.backend_developer.call_fix_code_bugs(factsheet).await;
+                    self.attributes.state = AgentState::UnitTesting;
+                }
+                AgentState::UnitTesting => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Authentication unit testing: running the backend checks",
+                    );
+
+                    if !self.backend_developer.run_unit_tests(factsheet).await? {
+                        self.attributes.state = AgentState::Working;
+                        continue;
+                    }
+
+                    if let Err(error_str) = self.check_login().await {
+                        PrintCommand::Issue
+                            .print_agent_msg(self.attributes.position.as_str(), &error_str);
+
+                        self.backend_developer.record_bugs(
+                            factsheet,
+                            BugStage::Login,
+                            error_str,
+                        )?;
+                        self.attributes.state = AgentState::Working;
+                        continue;
+                    }
+
+                    let api_endpoints: &mut Vec<RouteObject> =
+                        factsheet.api_endpoint_schema.get_or_insert_with(Vec::new);
+                    register_auth_routes(api_endpoints);
+                    self.backend_developer.finish_testing(factsheet);
+
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Authentication testing is completed.",
+                    );
+
+                    self.attributes.state = AgentState::Finishing;
+                }
+                _ => {
+                    self.attributes.state = AgentState::Finishing;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_register_auth_routes() {
+        let mut api_endpoints: Vec<RouteObject> = vec![RouteObject {
+            is_route_dynamic: false,
+            method: HttpMethod::Post,
+            request_body: None,
+            response: None,
+            route: "/signup".to_string(),
+        }];
+
+        register_auth_routes(&mut api_endpoints);
+        register_auth_routes(&mut api_endpoints);
+
+        let routes: Vec<&str> = api_endpoints
+            .iter()
+            .map(|route_object| route_object.route.as_str())
+            .collect();
+        assert_eq!(routes, vec!["/signup", "/login", "/logout"]);
+    }
+}
@@ -1,29 +1,81 @@
 use crate::ai_functions::aifunc_backend::{
-    print_backend_webserver_code, print_fixed_code, print_improved_webserver_code,
-    print_rest_api_endpoints,
+    print_backend_webserver_code, print_fixed_code, print_fixed_code_edits,
+    print_improved_code_edits, print_improved_webserver_code, print_rest_api_endpoints,
 };
 use crate::helpers::general::{
-    check_status_code, read_code_template_contents, read_executable_main_contents,
-    save_api_endpoints, save_backend_code, WEB_SERVER_PROJECT_PATH,
+    read_code_template_contents, record_backend_build, roll_back_backend_code, run_backend_project,
+    run_backend_project_on_scratch_data, save_api_endpoints, save_backend_code, save_patch_history,
 };
 
+use crate::helpers::code_audit::{blocking_findings_for_fix, blocking_severities, is_blocking};
 use crate::helpers::command_line::{confirm_safe_code, PrintCommand};
-use crate::helpers::general::ai_task_request;
+use crate::helpers::dependencies::update_project_dependencies;
+use crate::helpers::diagnostics::{
+    build_with_auto_fixes, run_quality_gate, BuildReport, QualityReport, DATABASE_SOURCE_FILE,
+    MAIN_SOURCE_FILE,
+};
+use crate::helpers::docker::external_urls_context;
+use crate::helpers::file_tree::FileTree;
+use crate::helpers::framework::{project_context, target_framework, Framework};
+use crate::helpers::fuzzer::{fuzz_duration, fuzz_routes, scan_panics, FuzzFinding, FuzzIssue};
+use crate::helpers::general::{ai_task_request, ai_task_request_decoded};
+use crate::helpers::patch::{apply_edit_blocks, parse_edit_blocks, EditBlock, PatchRecord};
+use crate::helpers::route_extractor::{merge_fallback_routes, ExtractedRoutes};
+use crate::helpers::route_tester::{test_routes, RouteTestResult};
+use crate::helpers::server::{find_free_port, ServerHandle, SERVER_READY_TIMEOUT};
 use crate::models::agents_basic::basic_agents::{AgentState, BasicAgent};
 
-use crate::models::agents::agent_traits::{FactSheet, RouteObject, SpecialFunctions};
+use crate::models::agents::agent_reviewer::AgentCodeReviewer;
+use crate::models::agents::agent_traits::{
+    AuditFinding, BackendFailure, FactSheet, HttpMethod, RestoredVersion, RouteObject, Severity,
+    SpecialFunctions,
+};
 
 use async_trait::async_trait;
-use core::panic;
 use crossterm::style::Color;
 use crossterm::style::{ResetColor, SetForegroundColor};
 use crossterm::ExecutableCommand;
 use reqwest::Client;
+use std::collections::HashMap;
 use std::fs;
 use std::io::{stdin, stdout};
-use std::process::{Command, Stdio};
 use std::time::Duration;
-use tokio::time;
+use strum_macros::Display;
+
+// Keeps the database designer's module in the backend's file tree and makes sure main.rs
+// declares it, so every later fix, audit and rollback sees it.
+fn attach_database_module(backend_code: &str, database_code: &str) -> String {
+    let mut file_tree: FileTree =
+        FileTree::from_code(backend_code).unwrap_or_else(|_| FileTree::single(backend_code));
+
+    file_tree
+        .files
+        .entry(DATABASE_SOURCE_FILE.to_string())
+        .or_insert_with(|| database_code.to_string());
+
+    if let Some(main) = file_tree.files.get_mut(MAIN_SOURCE_FILE) {
+        if !main.contains("mod database;") {
+            *main = format!("mod database;\n\n{}", main);
+        }
+    }
+
+    file_tree.to_code()
+}
+
+// The checks a backend version goes through, in order. Each one has its own bug limit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum BugStage {
+    Review,
+    Dependencies,
+    Build,
+    Lint,
+    Server,
+    Endpoints,
+    Fuzz,
+    Tests,
+    Login,
+}
 
 #[derive(Debug)]
 
@@ -31,6 +83,14 @@ pub struct AgentBackendDeveloper {
     attributes: BasicAgent,
     bug_errors: Option<String>,
     bug_count: u8,
+    // Failed rounds per stage. A stage that keeps failing ends the run, while fixing one stage
+    // and then failing the next does not.
+    stage_bug_counts: HashMap<BugStage, u8>,
+    patch_history: Vec<PatchRecord>,
+    last_built_code: Option<String>,
+    last_tested_code: Option<String>,
+    // Routes another agent tests itself, such as the auth routes that need a signup first.
+    untested_routes: Vec<RouteObject>,
 }
 
 impl AgentBackendDeveloper {
@@ -46,17 +106,39 @@ impl AgentBackendDeveloper {
             attributes,
             bug_errors: None,
             bug_count: 0,
+            stage_bug_counts: HashMap::new(),
+            patch_history: vec![],
+            last_built_code: None,
+            last_tested_code: None,
+            untested_routes: vec![],
         }
     }
 
     async fn call_initial_backend_code(&mut self, factsheet: &mut FactSheet) {
-        let code_template_str: String = read_code_template_contents();
-
-        let msg_context: String = format!(
-            "CODE TEMPLATE: {} \n PROJECT_DESCRIPTION: {} \n",
-            code_template_str, factsheet.project_description
+        let code_template_str: String =
+            target_framework().apply_port_convention(&read_code_template_contents());
+
+        let mut msg_context: String = format!(
+            "CODE TEMPLATE: {} \n PROJECT_DESCRIPTION: {} \n {}",
+            code_template_str,
+            factsheet.project_description,
+            project_context()
         );
 
+        if let Some(external_urls) = &factsheet.external_urls {
+            msg_context.push_str(&external_urls_context(external_urls));
+        }
+
+        if let Some(data_model) = &factsheet.data_model {
+            msg_context.push_str(&format!(
+                "DATA_MODEL: {} \n DATABASE_MODULE ({}): {} \n",
+                serde_json::to_string(&data_model.entities)
+                    .expect("Failed to serialize the data model."),
+                DATABASE_SOURCE_FILE,
+                data_model.database_code.as_deref().unwrap_or("None")
+            ));
+        }
+
         let ai_response: String = ai_task_request(
             msg_context,
             &self.attributes.position,
@@ -65,18 +147,68 @@ impl AgentBackendDeveloper {
         )
         .await;
 
-        save_backend_code(&ai_response);
+        let ai_response: String = match factsheet
+            .data_model
+            .as_ref()
+            .and_then(|data_model| data_model.database_code.as_deref())
+        {
+            Some(database_code) => attach_database_module(&ai_response, database_code),
+            None => ai_response,
+        };
+
+        save_backend_code(
+            &ai_response,
+            &self.attributes.position,
+            get_function_string!(print_backend_webserver_code),
+        );
         factsheet.backend_code = Some(ai_response);
     }
 
     async fn call_improved_backend_code(&mut self, factsheet: &mut FactSheet) {
         let code_template_str: String = read_code_template_contents();
+        let backend_code: String = factsheet.backend_code.clone().unwrap_or_default();
 
-        let msg_context: String = format!(
-            "CODE TEMPLATE: {:?} \n PROJECT_DESCRIPTION: {:?} \n",
-            factsheet.backend_code, factsheet
+        let mut edits_context: String = format!(
+            "PROJECT_DESCRIPTION: {:?} \n BACKEND_CODE: {} \n {}",
+            factsheet.project_description,
+            backend_code,
+            project_context()
+        );
+
+        if let Some(external_urls) = &factsheet.external_urls {
+            edits_context.push_str(&external_urls_context(external_urls));
+        }
+
+        let edits_response: String = ai_task_request(
+            edits_context,
+            &self.attributes.position,
+            get_function_string!(print_improved_code_edits),
+            print_improved_code_edits,
+        )
+        .await;
+
+        if let Some(patched_code) = self.apply_code_edits("improve", &backend_code, &edits_response)
+        {
+            save_backend_code(
+                &patched_code,
+                &self.attributes.position,
+                get_function_string!(print_improved_code_edits),
+            );
+            factsheet.backend_code = Some(patched_code);
+            return;
+        }
+
+        let mut msg_context: String = format!(
+            "CODE TEMPLATE: {:?} \n PROJECT_DESCRIPTION: {:?} \n {}",
+            factsheet.backend_code,
+            factsheet,
+            project_context()
         );
 
+        if let Some(external_urls) = &factsheet.external_urls {
+            msg_context.push_str(&external_urls_context(external_urls));
+        }
+
         let ai_response: String = ai_task_request(
             msg_context,
             &self.attributes.position,
@@ -85,17 +217,52 @@ impl AgentBackendDeveloper {
         )
         .await;
 
-        save_backend_code(&ai_response);
+        save_backend_code(
+            &ai_response,
+            &self.attributes.position,
+            get_function_string!(print_improved_webserver_code),
+        );
         factsheet.backend_code = Some(ai_response);
     }
 
-    async fn call_fix_code_bugs(&mut self, factsheet: &mut FactSheet) {
+    // Also drives the fixes for the test writer and auth agents, so every agent shares the edit
+    // blocks, patch history and bug limit.
+    pub async fn call_fix_code_bugs(&mut self, factsheet: &mut FactSheet) {
         let code_template_str: String = read_code_template_contents();
+        let backend_code: String = factsheet.backend_code.clone().unwrap_or_default();
+        let bug_errors: String = self.bug_errors.clone().unwrap_or_default();
+
+        let edits_context: String = format!(
+            "BROKEN_CODE: {} \n ERROR_BUGS: {} \n {}",
+            backend_code,
+            bug_errors,
+            project_context()
+        );
+
+        let edits_response: String = ai_task_request(
+            edits_context,
+            &self.attributes.position,
+            get_function_string!(print_fixed_code_edits),
+            print_fixed_code_edits,
+        )
+        .await;
+
+        if let Some(patched_code) = self.apply_code_edits("fix", &backend_code, &edits_response) {
+            save_backend_code(
+                &patched_code,
+                &self.attributes.position,
+                get_function_string!(print_fixed_code_edits),
+            );
+            factsheet.backend_code = Some(patched_code);
+            return;
+        }
 
         let msg_context: String = format!(
-            "BROKEN_CODE: {:?} \n ERROR_BUGS: {:?} \n
+            "BROKEN_CODE: {:?} \n ERROR_BUGS: {} \n {}
             THIS FUNCTION ONLY OUTPUTS THE CODE. JUST THE WORKING CODE NOTHING MORE",
-            factsheet.backend_code, self.bug_errors
+            factsheet.backend_code,
+            bug_errors,
+            project_context()
         );
 
         let ai_response: String = ai_task_request(
@@ -106,16 +273,472 @@ impl AgentBackendDeveloper {
         )
         .await;
 
-        save_backend_code(&ai_response);
+        save_backend_code(
+            &ai_response,
+            &self.attributes.position,
+            get_function_string!(print_fixed_code),
+        );
         factsheet.backend_code = Some(ai_response);
     }
 
-    async fn call_extract_api_endpoints(&self) -> String {
-        let backend_code = read_executable_main_contents();
+    // Returns the patched code, or None when the edits can't be applied and a full rewrite is needed.
+    fn apply_code_edits(&mut self, step: &str, source: &str, response: &str) -> Option<String> {
+        let result: Result<(Vec<EditBlock>, String), String> = parse_edit_blocks(response)
+            .and_then(|edits| {
+                let patched_code: String = apply_edit_blocks(source, &edits)?;
+                Ok((edits, patched_code))
+            });
+
+        let (record, patched_code) = match result {
+            Ok((edits, patched_code)) => (
+                PatchRecord {
+                    step: step.to_string(),
+                    edits,
+                    fell_back_to_rewrite: false,
+                    failure: None,
+                },
+                Some(patched_code),
+            ),
+            Err(failure) => {
+                let fallback_msg: String = format!(
+                    "Edits could not be applied, rewriting the file: {}",
+                    failure
+                );
+                PrintCommand::Issue
+                    .print_agent_msg(self.attributes.position.as_str(), fallback_msg.as_str());
+
+                (
+                    PatchRecord {
+                        step: step.to_string(),
+                        edits: vec![],
+                        fell_back_to_rewrite: true,
+                        failure: Some(failure),
+                    },
+                    None,
+                )
+            }
+        };
 
-        let msg_context: String = format!("CODE_INPUT: {}", backend_code);
+        self.patch_history.push(record);
 
-        let ai_response: String = ai_task_request(
+        let patch_history_str: String = serde_json::to_string_pretty(&self.patch_history)
+            .expect("Failed to serialize the patch history.");
+        save_patch_history(&patch_history_str);
+
+        patched_code
+    }
+
+    // Sends the errors back to the Working state. After three failed rounds of the same stage the
+    // agent gives up and the returned error carries the failure recorded on the fact sheet.
+    pub fn record_bugs(
+        &mut self,
+        factsheet: &mut FactSheet,
+        stage: BugStage,
+        error_str: String,
+    ) -> Result<(), String> {
+        self.bug_count += 1;
+        self.bug_errors = Some(error_str);
+
+        let stage_bug_count: &mut u8 = self.stage_bug_counts.entry(stage).or_insert(0);
+        *stage_bug_count += 1;
+        let stage_bug_count: u8 = *stage_bug_count;
+
+        if stage_bug_count > 2 {
+            PrintCommand::Issue.print_agent_msg(
+                self.attributes.position.as_str(),
+                "Too many bugs found in code. Exiting",
+            );
+
+            let failure: BackendFailure = BackendFailure {
+                reason: format!(
+                    "Too many bugs found in the backend code at the {} stage",
+                    stage
+                ),
+                attempts: stage_bug_count,
+                last_errors: self.bug_errors.clone(),
+                restored: self.restore_working_code(factsheet),
+            };
+
+            let failure_str: String = failure.describe();
+            factsheet.backend_failure = Some(failure);
+
+            return Err(failure_str);
+        }
+
+        self.attributes.state = AgentState::Working;
+        Ok(())
+    }
+
+    // Agents that take over code which already passed the endpoint tests restore it when they
+    // give up.
+    pub fn set_tested_code(&mut self, backend_code: &str) {
+        self.last_tested_code = Some(backend_code.to_string());
+    }
+
+    // Leaves these routes out of the generic endpoint tests. The agent that owns them runs its
+    // own checks after run_unit_tests passes.
+    pub fn skip_route_tests(&mut self, routes: Vec<RouteObject>) {
+        self.untested_routes = routes;
+    }
+
+    pub fn bug_errors(&self) -> Option<&str> {
+        self.bug_errors.as_deref()
+    }
+
+    // Puts the last version that passed the endpoint tests, or else the last one that built, back
+    // in place of the broken code. Without either, the project history is the last resort.
+    fn restore_working_code(&self, factsheet: &mut FactSheet) -> Option<RestoredVersion> {
+        let known_good: Option<(&String, RestoredVersion)> = self
+            .last_tested_code
+            .as_ref()
+            .map(|code| (code, RestoredVersion::Tested))
+            .or_else(|| {
+                self.last_built_code
+                    .as_ref()
+                    .map(|code| (code, RestoredVersion::Built))
+            });
+
+        if let Some((code, version)) = known_good {
+            save_backend_code(code, &self.attributes.position, "restore last working code");
+            record_backend_build(true);
+            factsheet.backend_code = Some(code.clone());
+            return Some(version);
+        }
+
+        let restored_code: String = roll_back_backend_code(&self.attributes.position)?;
+        factsheet.backend_code = Some(restored_code);
+        Some(RestoredVersion::Built)
+    }
+
+    // Runs every check on the backend code in order: review, dependencies, build, lints, server
+    // start, endpoint tests and fuzzing. Returns false when a check failed and its errors were
+    // recorded for the next fix. Other agents that change the backend code run the same checks.
+    pub async fn run_unit_tests(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        PrintCommand::UnitTest.print_agent_msg(
+            self.attributes.position.as_str(),
+            "Backend code unit testing: ensuring safe code",
+        );
+
+        AgentCodeReviewer::new().execute(factsheet).await?;
+        let findings: &[AuditFinding] = factsheet.audit_findings.as_deref().unwrap_or_default();
+
+        let blocking: Vec<Severity> = blocking_severities();
+
+        if is_blocking(findings, &blocking) {
+            let error_str: String = blocking_findings_for_fix(findings, &blocking);
+
+            PrintCommand::Issue.print_agent_msg(
+                self.attributes.position.as_str(),
+                "Backend code has review findings with a blocking severity",
+            );
+            self.record_bugs(factsheet, BugStage::Review, error_str)?;
+            return Ok(false);
+        }
+
+        if !confirm_safe_code(findings) {
+            return Err("Stopped before running the backend code.".into());
+        }
+
+        match update_project_dependencies(factsheet.backend_code.as_deref().unwrap_or_default()) {
+            Ok(added) => {
+                for dependency in added {
+                    let added_msg: String =
+                        format!("Added {} to Cargo.toml", dependency.describe());
+                    PrintCommand::UnitTest
+                        .print_agent_msg(self.attributes.position.as_str(), added_msg.as_str());
+                }
+            }
+            Err(error_str) => {
+                PrintCommand::Issue.print_agent_msg(
+                    self.attributes.position.as_str(),
+                    "Backend code uses crates that are not allowed",
+                );
+                self.record_bugs(factsheet, BugStage::Dependencies, error_str)?;
+                return Ok(false);
+            }
+        }
+
+        PrintCommand::UnitTest.print_agent_msg(
+            self.attributes.position.as_str(),
+            "Backend code unit testing: building the project",
+        );
+
+        if let Some(backend_code) = &factsheet.backend_code {
+            let converted_code: String = target_framework().apply_port_convention(backend_code);
+
+            if &converted_code != backend_code {
+                save_backend_code(
+                    &converted_code,
+                    &self.attributes.position,
+                    "port convention",
+                );
+                factsheet.backend_code = Some(converted_code);
+            }
+        }
+
+        let build_report: BuildReport =
+            build_with_auto_fixes(factsheet.backend_code.as_deref().unwrap_or_default());
+
+        if build_report.auto_fixes > 0 {
+            let fixes_msg: String = format!(
+                "Applied {} compiler suggested fixes",
+                build_report.auto_fixes
+            );
+            PrintCommand::UnitTest
+                .print_agent_msg(self.attributes.position.as_str(), fixes_msg.as_str());
+
+            factsheet.backend_code = Some(build_report.backend_code.clone());
+        }
+
+        if build_report.success {
+            self.last_built_code = Some(build_report.backend_code.clone());
+
+            PrintCommand::UnitTest.print_agent_msg(
+                self.attributes.position.as_str(),
+                "Backend code unit testing: Test server build succesful.",
+            );
+        } else {
+            let error_str: String = build_report.errors_for_fix();
+
+            self.record_bugs(factsheet, BugStage::Build, error_str)?;
+            return Ok(false);
+        }
+
+        PrintCommand::UnitTest.print_agent_msg(
+            self.attributes.position.as_str(),
+            "Backend code unit testing: checking formatting and lints",
+        );
+
+        let quality_report: QualityReport = run_quality_gate();
+
+        if !quality_report.is_passing() {
+            let error_str: String = quality_report.errors_for_fix(&build_report.backend_code);
+
+            self.record_bugs(factsheet, BugStage::Lint, error_str)?;
+            return Ok(false);
+        }
+
+        let api_endpoints: Vec<RouteObject> = self
+            .call_extract_api_endpoints(&build_report.backend_code)
+            .await;
+
+        factsheet.api_endpoint_schema = Some(api_endpoints.clone());
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        let port: u16 = find_free_port()?;
+        let base_url: String = format!("http://localhost:{}", port);
+
+        let start_msg: String = format!("Starting web server on port {}", port);
+        PrintCommand::UnitTest
+            .print_agent_msg(self.attributes.position.as_str(), start_msg.as_str());
+
+        let mut run_backend_server: ServerHandle = run_backend_project(port);
+
+        if let Err(error_str) = run_backend_server
+            .wait_until_ready(&client, &base_url, SERVER_READY_TIMEOUT)
+            .await
+        {
+            PrintCommand::Issue
+                .print_agent_msg(self.attributes.position.as_str(), "Server failed to start");
+            self.record_bugs(factsheet, BugStage::Server, error_str)?;
+            return Ok(false);
+        }
+
+        PrintCommand::UnitTest.print_agent_msg(
+            self.attributes.position.as_str(),
+            "Launching tests on the server",
+        );
+
+        let tested_routes: Vec<RouteObject> = api_endpoints
+            .iter()
+            .filter(|route_object| {
+                !self.untested_routes.iter().any(|untested| {
+                    untested.route == route_object.route && untested.method == route_object.method
+                })
+            })
+            .cloned()
+            .collect();
+
+        let results: Vec<RouteTestResult> = test_routes(&client, &base_url, &tested_routes).await;
+
+        for result in &results {
+            let report_msg: String = format!(
+                "{} {} ({}): {}",
+                result.method.to_string().to_uppercase(),
+                result.route,
+                result.url,
+                result.message
+            );
+
+            if result.passed {
+                PrintCommand::UnitTest
+                    .print_agent_msg(self.attributes.position.as_str(), report_msg.as_str());
+            } else {
+                PrintCommand::Issue
+                    .print_agent_msg(self.attributes.position.as_str(), report_msg.as_str());
+            }
+        }
+
+        let passed_count: usize = results.iter().filter(|r| r.passed).count();
+        let summary_msg: String = format!("{}/{} endpoints passed", passed_count, results.len());
+        PrintCommand::UnitTest
+            .print_agent_msg(self.attributes.position.as_str(), summary_msg.as_str());
+
+        run_backend_server.stop();
+
+        let failures: Vec<String> = results
+            .iter()
+            .filter(|result| !result.passed)
+            .map(|result| {
+                format!(
+                    "{} {} failed: {}",
+                    result.method.to_string().to_uppercase(),
+                    result.route,
+                    result.message
+                )
+            })
+            .collect();
+
+        if !failures.is_empty() {
+            self.record_bugs(factsheet, BugStage::Endpoints, failures.join("\n"))?;
+            return Ok(false);
+        }
+
+        self.last_tested_code = Some(build_report.backend_code.clone());
+
+        if let Some(duration) = fuzz_duration() {
+            let fuzz_msg: String = format!("Fuzzing endpoints for {} seconds", duration.as_secs());
+            PrintCommand::UnitTest
+                .print_agent_msg(self.attributes.position.as_str(), fuzz_msg.as_str());
+
+            let mut fuzz_server: ServerHandle = run_backend_project_on_scratch_data(port);
+
+            let mut findings: Vec<FuzzFinding> = match fuzz_server
+                .wait_until_ready(&client, &base_url, SERVER_READY_TIMEOUT)
+                .await
+            {
+                Ok(()) => {
+                    fuzz_routes(
+                        &client,
+                        &base_url,
+                        &api_endpoints,
+                        duration,
+                        &mut fuzz_server,
+                    )
+                    .await
+                }
+                Err(error_str) => {
+                    self.record_bugs(factsheet, BugStage::Fuzz, error_str)?;
+                    return Ok(false);
+                }
+            };
+
+            fuzz_server.stop();
+
+            findings.extend(scan_panics(&fuzz_server.output()).into_iter().map(|line| {
+                FuzzFinding {
+                    route: "*".to_string(),
+                    method: HttpMethod::Get,
+                    input: "server output".to_string(),
+                    issue: FuzzIssue::Panic(line),
+                }
+            }));
+
+            let (findings, transport_errors): (Vec<FuzzFinding>, Vec<FuzzFinding>) = findings
+                .into_iter()
+                .partition(|finding| finding.issue.is_bug());
+
+            for transport_error in &transport_errors {
+                PrintCommand::UnitTest.print_agent_msg(
+                    self.attributes.position.as_str(),
+                    transport_error.describe().as_str(),
+                );
+            }
+
+            if !findings.is_empty() {
+                let fuzz_errors: Vec<String> = findings.iter().map(FuzzFinding::describe).collect();
+
+                for fuzz_error in &fuzz_errors {
+                    PrintCommand::Issue
+                        .print_agent_msg(self.attributes.position.as_str(), fuzz_error.as_str());
+                }
+
+                self.record_bugs(
+                    factsheet,
+                    BugStage::Fuzz,
+                    format!(
+                    "The server must reject bad input with a 4xx status instead of failing:\n{}",
+                    fuzz_errors.join("\n")
+                ),
+                )?;
+                return Ok(false);
+            }
+        }
+
+        let warning_summary: Vec<String> = quality_report.warning_summary();
+
+        for warning_line in &warning_summary {
+            let warning_msg: String = format!("Remaining warning: {}", warning_line);
+            PrintCommand::UnitTest
+                .print_agent_msg(self.attributes.position.as_str(), warning_msg.as_str());
+        }
+
+        factsheet.remaining_warnings = Some(warning_summary);
+
+        Ok(true)
+    }
+
+    // Resets the bug counts once the code passed every check and saves the tested endpoints.
+    pub fn finish_testing(&mut self, factsheet: &mut FactSheet) {
+        self.bug_count = 0;
+        self.stage_bug_counts.clear();
+        factsheet.backend_failure = None;
+
+        let api_endpoints_str: String =
+            serde_json::to_string(factsheet.api_endpoint_schema.as_deref().unwrap_or_default())
+                .expect("Failed to serialize the API endpoints.");
+        save_api_endpoints(&api_endpoints_str);
+
+        PrintCommand::UnitTest.print_agent_msg(
+            self.attributes.position.as_str(),
+            "Backend testing is completed.",
+        );
+
+        self.attributes.state = AgentState::Finishing;
+    }
+
+    async fn call_extract_api_endpoints(&self, backend_code: &str) -> Vec<RouteObject> {
+        let rust_sources: String = FileTree::from_code(backend_code)
+            .map(|file_tree| file_tree.rust_sources())
+            .unwrap_or_else(|_| backend_code.to_string());
+
+        let mut extracted: ExtractedRoutes = match target_framework().extract_routes(&rust_sources)
+        {
+            Ok(extracted) if !extracted.routes.is_empty() => extracted,
+            _ => return self.call_llm_api_endpoints(backend_code).await,
+        };
+
+        if !extracted.unresolved.is_empty() {
+            let fallback: Vec<RouteObject> = self.call_llm_api_endpoints(backend_code).await;
+            merge_fallback_routes(&mut extracted, &fallback);
+        }
+
+        extracted.routes
+    }
+
+    async fn call_llm_api_endpoints(&self, backend_code: &str) -> Vec<RouteObject> {
+        let framework: Framework = target_framework();
+        let msg_context: String =
+            format!("CODE_INPUT: {} \n FRAMEWORK: {}", backend_code, framework);
+
+        let mut api_endpoints: Vec<RouteObject> = ai_task_request_decoded::<Vec<RouteObject>>(
             msg_context,
             &self.attributes.position,
             get_function_string!(print_rest_api_endpoints),
@@ -123,7 +746,11 @@ impl AgentBackendDeveloper {
         )
         .await;
 
-        ai_response
+        for route_object in &mut api_endpoints {
+            route_object.route = framework.normalize_route(&route_object.route);
+        }
+
+        api_endpoints
     }
 }
 
@@ -155,142 +782,11 @@ impl SpecialFunctions for AgentBackendDeveloper {
                     continue;
                 }
                 AgentState::UnitTesting => {
-                    PrintCommand::UnitTest.print_agent_msg(
-                        &self.attributes.position.as_str(),
-                        "Backend code unit testing: ensuring safe code",
-                    );
+                    let is_passing: bool = self.run_unit_tests(factsheet).await?;
 
-                    let is_safe_code: bool = confirm_safe_code();
-
-                    if !is_safe_code {
-                        panic!("Stopped mid way.");
+                    if is_passing {
+                        self.finish_testing(factsheet);
                     }
-
-                    PrintCommand::UnitTest.print_agent_msg(
-                        &self.attributes.position.as_str(),
-                        "Backend code unit testing: building the project",
-                    );
-
-                    let build_backend_server: std::process::Output = Command::new("cargo")
-                        .arg("build")
-                        .current_dir(WEB_SERVER_PROJECT_PATH)
-                        .stdout(Stdio::piped())
-                        .stderr(Stdio::piped())
-                        .output()
-                        .expect("Failed to build the backend application.");
-
-                    if build_backend_server.status.success() {
-                        self.bug_count = 0;
-                        PrintCommand::UnitTest.print_agent_msg(
-                            &self.attributes.position.as_str(),
-                            "Backend code unit testing: Test server build succesful.",
-                        );
-                    } else {
-                        let error_arr: Vec<u8> = build_backend_server.stderr;
-                        let error_str: String = String::from_utf8(error_arr).unwrap();
-
-                        self.bug_count += 1;
-                        self.bug_errors = Some(error_str);
-
-                        if self.bug_count > 2 {
-                            PrintCommand::Issue.print_agent_msg(
-                                &self.attributes.position.as_str(),
-                                "Too many bugs found in code. Exiting",
-                            );
-
-                            panic!("Error: too many bugs.");
-                        }
-
-                        self.attributes.state = AgentState::Working;
-                        continue;
-                    }
-
-                    let api_endpoints_str: String = self.call_extract_api_endpoints().await;
-                    let api_endpoints: Vec<RouteObject> =
-                        serde_json::from_str(&api_endpoints_str.as_str())
-                            .expect("Failed to create the API endpoints.");
-
-                    let check_endpoints: Vec<RouteObject> = api_endpoints
-                        .iter()
-                        .filter(|&route_object| {
-                            route_object.method == "get" && route_object.is_route_dynamic == "false"
-                        })
-                        .cloned()
-                        .collect();
-
-                    factsheet.api_endpoint_schema = Some(check_endpoints.clone());
-
-                    PrintCommand::UnitTest
-                        .print_agent_msg(&self.attributes.position.as_str(), "Starting web server");
-
-                    let mut run_backend_server: std::process::Child = Command::new("cargo")
-                        .arg("run")
-                        .current_dir(WEB_SERVER_PROJECT_PATH)
-                        .stdout(Stdio::piped())
-                        .stderr(Stdio::piped())
-                        .spawn()
-                        .expect("Failed to run the backend application.");
-
-                    PrintCommand::UnitTest.print_agent_msg(
-                        &self.attributes.position.as_str(),
-                        "Launching tests on the server",
-                    );
-
-                    let seconds_sleep: Duration = Duration::from_secs(5);
-                    time::sleep(seconds_sleep).await;
-
-                    for endpoint in check_endpoints {
-                        let testing_msg: String = format!("Testing endpoint: {}", endpoint.route);
-                        PrintCommand::UnitTest.print_agent_msg(
-                            &self.attributes.position.as_str(),
-                            &testing_msg.as_str(),
-                        );
-
-                        let url: String = format!("http://localhost:8080{}", endpoint.route);
-
-                        let client = Client::builder()
-                            .timeout(Duration::from_secs(5))
-                            .build()
-                            .unwrap();
-
-                        match check_status_code(&client, &url).await {
-                            Ok(status_code) => {
-                                if status_code != 200 {
-                                    let err_msg: String =
-                                        format!("Failed to call the endpoint: {}", endpoint.route);
-
-                                    PrintCommand::Issue.print_agent_msg(
-                                        &self.attributes.position.as_str(),
-                                        &err_msg.as_str(),
-                                    );
-                                }
-                            }
-                            Err(e) => {
-                                run_backend_server
-                                    .kill()
-                                    .expect("Failed to kill backend server.");
-
-                                let err_msg: String = format!("Failed to kill the server.");
-
-                                PrintCommand::Issue.print_agent_msg(
-                                    &self.attributes.position.as_str(),
-                                    &err_msg.as_str(),
-                                );
-                            }
-                        }
-                    }
-
-                    save_api_endpoints(&api_endpoints_str);
-                    PrintCommand::UnitTest.print_agent_msg(
-                        &self.attributes.position.as_str(),
-                        "Backend testing is completed.",
-                    );
-
-                    run_backend_server
-                        .kill()
-                        .expect("Failed to kill the backend server.");
-
-                    self.attributes.state = AgentState::Finishing;
                 }
                 _ => {}
             }
@@ -304,6 +800,28 @@ impl SpecialFunctions for AgentBackendDeveloper {
 mod tests {
     use super::*;
 
+    #[test]
+    fn tests_attach_database_module() {
+        let backend_code: &str = "fn main() {}\n";
+        let attached: String = attach_database_module(backend_code, "pub struct Task {}\n");
+
+        let file_tree: FileTree = FileTree::from_code(&attached).unwrap();
+        assert_eq!(
+            file_tree.files[MAIN_SOURCE_FILE],
+            "mod database;\n\nfn main() {}\n"
+        );
+        assert_eq!(
+            file_tree.files[DATABASE_SOURCE_FILE],
+            "pub struct Task {}\n"
+        );
+
+        // A module the backend already wrote is kept as is.
+        assert_eq!(
+            attach_database_module(&attached, "pub struct Other {}\n"),
+            attached
+        );
+    }
+
     #[tokio::test]
     async fn tests_backend_agent() {
         let mut agent = AgentBackendDeveloper::new();
@@ -318,9 +836,12 @@ mod tests {
                 },
             
             "external_urls": [],
-            
+            "data_model": null,
+
             "backend_code": null,
-            "api_endpoint_schema": null
+            "audit_findings": null,
+            "api_endpoint_schema": null,
+            "frontend_code": null
         }
         "#;
 
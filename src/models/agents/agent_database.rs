@@ -0,0 +1,203 @@
+use crate::ai_functions::aifunc_database::{print_data_model, print_database_code};
+use crate::helpers::command_line::PrintCommand;
+use crate::helpers::general::{ai_task_request, save_database_code, try_ai_task_request_decoded};
+use crate::models::agents::agent_traits::{DataModel, EntityModel, FactSheet, SpecialFunctions};
+use crate::models::agents_basic::basic_agents::{AgentState, BasicAgent};
+
+use async_trait::async_trait;
+
+#[derive(Debug)]
+pub struct AgentDatabaseDesigner {
+    attributes: BasicAgent,
+    bug_errors: Option<String>,
+    bug_count: u8,
+}
+
+impl AgentDatabaseDesigner {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Designs the data model and data access code for the backend database"
+                .to_string(),
+            position: "Database designer".to_string(),
+            state: AgentState::Discovery,
+            memory: vec![],
+        };
+
+        Self {
+            attributes,
+            bug_errors: None,
+            bug_count: 0,
+        }
+    }
+
+    async fn call_data_model(&mut self, factsheet: &mut FactSheet) -> Result<(), String> {
+        let msg_context: String = format!("{:?}", factsheet.project_description);
+
+        let ai_response: Vec<EntityModel> = try_ai_task_request_decoded::<Vec<EntityModel>>(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_data_model),
+            print_data_model,
+        )
+        .await?;
+
+        factsheet.data_model = Some(DataModel {
+            entities: ai_response,
+            database_code: None,
+        });
+
+        Ok(())
+    }
+
+    async fn call_database_code(&mut self, factsheet: &mut FactSheet) {
+        let data_model: &mut DataModel = factsheet
+            .data_model
+            .as_mut()
+            .expect("No data model on fact sheet");
+
+        let mut msg_context: String = format!(
+            "DATA_MODEL: {}",
+            serde_json::to_string(&data_model.entities)
+                .expect("Failed to serialize the data model.")
+        );
+
+        if let Some(bug_errors) = &self.bug_errors {
+            msg_context.push_str(&format!(" \n PREVIOUS_ATTEMPT_ERRORS: {}", bug_errors));
+        }
+
+        let ai_response: String = ai_task_request(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_database_code),
+            print_database_code,
+        )
+        .await;
+
+        save_database_code(&ai_response);
+        data_model.database_code = Some(ai_response);
+    }
+
+    // Sends the agent back to `state` with the errors, and gives up after three failed rounds.
+    fn record_bugs(&mut self, error_str: String, state: AgentState) -> Result<(), String> {
+        PrintCommand::Issue.print_agent_msg(self.attributes.position.as_str(), &error_str);
+
+        self.bug_count += 1;
+        self.bug_errors = Some(error_str);
+
+        if self.bug_count > 2 {
+            return Err(format!(
+                "Too many bugs found in the database code: {}",
+                self.bug_errors.as_deref().unwrap_or_default()
+            ));
+        }
+
+        self.attributes.state = state;
+        Ok(())
+    }
+}
+
+fn find_missing_entities(database_code: &str, entities: &[EntityModel]) -> Vec<String> {
+    entities
+        .iter()
+        .map(|entity| entity.name.clone())
+        .filter(|name| !database_code.contains(&format!("struct {}", name)))
+        .collect()
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentDatabaseDesigner {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let is_crud_required: bool = factsheet
+            .project_scope
+            .map(|project_scope| project_scope.is_crud_required)
+            .unwrap_or(false);
+
+        if !is_crud_required {
+            self.attributes.state = AgentState::Finishing;
+        }
+
+        while self.attributes.state != AgentState::Finishing {
+            match self.attributes.state {
+                AgentState::Discovery => match self.call_data_model(factsheet).await {
+                    Ok(()) => {
+                        self.bug_errors = None;
+                        self.attributes.state = AgentState::Working;
+                    }
+                    Err(error_str) => self.record_bugs(error_str, AgentState::Discovery)?,
+                },
+                AgentState::Working => {
+                    self.call_database_code(factsheet).await;
+                    self.attributes.state = AgentState::UnitTesting;
+                }
+                AgentState::UnitTesting => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Database code unit testing: checking every entity has a struct",
+                    );
+
+                    let data_model: &DataModel = factsheet
+                        .data_model
+                        .as_ref()
+                        .expect("No data model on fact sheet");
+
+                    let database_code: &str = data_model.database_code.as_deref().unwrap_or("");
+                    let missing: Vec<String> =
+                        find_missing_entities(database_code, &data_model.entities);
+
+                    if !missing.is_empty() {
+                        let error_str: String =
+                            format!("These entities have no struct: {}", missing.join(", "));
+                        self.record_bugs(error_str, AgentState::Working)?;
+                        continue;
+                    }
+
+                    self.bug_count = 0;
+                    self.bug_errors = None;
+                    self.attributes.state = AgentState::Finishing;
+                }
+                _ => {
+                    self.attributes.state = AgentState::Finishing;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_find_missing_entities() {
+        let entities_str: &str = r#"
+        [
+            {
+                "name": "Task",
+                "fields": [
+                    { "name": "id", "field_type": "number" },
+                    { "name": "title", "field_type": "string" }
+                ]
+            },
+            {
+                "name": "User",
+                "fields": [{ "name": "id", "field_type": "number" }]
+            }
+        ]
+        "#;
+
+        let entities: Vec<EntityModel> = serde_json::from_str(entities_str).unwrap();
+        let database_code: &str = "pub struct Task { pub id: u64, pub title: String }";
+
+        let missing: Vec<String> = find_missing_entities(database_code, &entities);
+        assert_eq!(missing, vec!["User".to_string()]);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::helpers::command_line::PrintCommand;
+use crate::helpers::docker::{
+    container_port, external_url_env_vars, generate_compose, generate_dockerfile,
+    generate_dockerignore, lint_compose, lint_dockerfile, package_name, unread_env_vars,
+};
+use crate::helpers::general::{read_project_file, save_project_file};
+use crate::models::agents::agent_traits::{FactSheet, SpecialFunctions};
+use crate::models::agents_basic::basic_agents::{AgentState, BasicAgent};
+
+use async_trait::async_trait;
+
+#[derive(Debug)]
+pub struct AgentDevOps {
+    attributes: BasicAgent,
+    port: u16,
+    dockerfile: String,
+    dockerignore: String,
+    compose: String,
+}
+
+impl AgentDevOps {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Writes the container build and compose files for the backend server"
+                .to_string(),
+            position: "DevOps engineer".to_string(),
+            state: AgentState::Discovery,
+            memory: vec![],
+        };
+
+        Self {
+            attributes,
+            port: container_port(),
+            dockerfile: String::new(),
+            dockerignore: String::new(),
+            compose: String::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentDevOps {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        while self.attributes.state != AgentState::Finishing {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    let cargo_toml: String = read_project_file("Cargo.toml");
+                    let binary_name: String = package_name(&cargo_toml)
+                        .ok_or("No package name in the project Cargo.toml.")?;
+
+                    let env_vars: Vec<(String, String)> = external_url_env_vars(
+                        factsheet.external_urls.as_deref().unwrap_or_default(),
+                    );
+
+                    // The compose file can only pass URLs to code that reads them.
+                    let backend_code: &str = factsheet.backend_code.as_deref().unwrap_or_default();
+                    for name in unread_env_vars(backend_code, &env_vars) {
+                        PrintCommand::Issue.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            &format!("The backend code never reads {}", name),
+                        );
+                    }
+
+                    self.dockerfile = generate_dockerfile(&binary_name, self.port);
+                    self.dockerignore = generate_dockerignore();
+                    self.compose = generate_compose(&binary_name, self.port, &env_vars);
+
+                    self.attributes.state = AgentState::UnitTesting;
+                }
+                AgentState::UnitTesting => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Container files testing: linting Dockerfile and compose file",
+                    );
+
+                    let mut issues: Vec<String> = lint_dockerfile(&self.dockerfile, self.port);
+                    issues.extend(lint_compose(&self.compose, self.port));
+
+                    if !issues.is_empty() {
+                        for issue in &issues {
+                            PrintCommand::Issue
+                                .print_agent_msg(self.attributes.position.as_str(), issue);
+                        }
+
+                        return Err("Container files failed linting.".into());
+                    }
+
+                    save_project_file("Dockerfile", &self.dockerfile);
+                    save_project_file(".dockerignore", &self.dockerignore);
+                    save_project_file("compose.yaml", &self.compose);
+
+                    self.attributes.state = AgentState::Finishing;
+                }
+                _ => {
+                    self.attributes.state = AgentState::Finishing;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::general::set_run_workspace;
+    use crate::helpers::templates::TemplateSource;
+    use crate::helpers::workspace::RunWorkspace;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn tests_devops_writes_container_files() {
+        let runs_dir: PathBuf =
+            std::env::temp_dir().join(format!("agent_devops_{}", std::process::id()));
+        let workspace: RunWorkspace = RunWorkspace::create(&runs_dir, "devops", 0).unwrap();
+        TemplateSource::Builtin("actix-json-db")
+            .materialize(&workspace.project_dir())
+            .unwrap();
+        set_run_workspace(workspace.clone());
+
+        let mut factsheet: FactSheet = FactSheet {
+            project_description: "Show the latest forex prices".to_string(),
+            project_scope: None,
+            external_urls: Some(vec!["https://api.exchangerate.host/latest".to_string()]),
+            data_model: None,
+            backend_code: Some(
+                "let url = std::env::var(\"EXTERNAL_URL_1\").unwrap_or_default();".to_string(),
+            ),
+            audit_findings: None,
+            api_endpoint_schema: None,
+            frontend_code: None,
+            backend_failure: None,
+            remaining_warnings: None,
+            backend_base_url: None,
+        };
+
+        let mut agent: AgentDevOps = AgentDevOps::new();
+        agent
+            .execute(&mut factsheet)
+            .await
+            .expect("Unable to execute the devops agent.");
+
+        let project_dir: PathBuf = workspace.project_dir();
+        let dockerfile: String = fs::read_to_string(project_dir.join("Dockerfile")).unwrap();
+        let compose: String = fs::read_to_string(project_dir.join("compose.yaml")).unwrap();
+
+        assert!(dockerfile.contains("ENV HOST=0.0.0.0"));
+        assert!(dockerfile.contains(&format!("EXPOSE {}", agent.port)));
+        assert!(dockerfile.contains("target/release/web_server"));
+        assert!(compose.contains("EXTERNAL_URL_1: \"https://api.exchangerate.host/latest\""));
+        assert!(project_dir.join(".dockerignore").is_file());
+
+        fs::remove_dir_all(runs_dir).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+use crate::ai_functions::aifunc_docs::print_project_readme;
+use crate::helpers::command_line::PrintCommand;
+use crate::helpers::general::{ai_task_request, save_openapi_spec, save_readme};
+use crate::helpers::openapi::{build_openapi_document, validate_openapi_yaml};
+use crate::helpers::route_tester::sample_value;
+use crate::models::agents::agent_traits::{FactSheet, FieldSchema, RouteObject, SpecialFunctions};
+use crate::models::agents_basic::basic_agents::{AgentState, BasicAgent};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+#[derive(Debug)]
+pub struct AgentDocumentation {
+    attributes: BasicAgent,
+    openapi_yaml: Option<String>,
+}
+
+impl AgentDocumentation {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Writes the README and OpenAPI specification for the generated project"
+                .to_string(),
+            position: "Technical writer".to_string(),
+            state: AgentState::Discovery,
+            memory: vec![],
+        };
+
+        Self {
+            attributes,
+            openapi_yaml: None,
+        }
+    }
+
+    async fn call_project_readme(&mut self, factsheet: &FactSheet, routes: &[RouteObject]) {
+        let msg_context: String = format!(
+            "PROJECT_DESCRIPTION: {} \n PROJECT_SCOPE: {:?} \n API_ENDPOINT_SCHEMA: {:?} \n",
+            factsheet.project_description, factsheet.project_scope, routes
+        );
+
+        let ai_response: String = ai_task_request(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_project_readme),
+            print_project_readme,
+        )
+        .await;
+
+        let readme: String = format!(
+            "{}\n\n{}\n{}",
+            ai_response.trim_end(),
+            endpoint_table(routes),
+            curl_examples(routes)
+        );
+
+        save_readme(&readme);
+    }
+}
+
+fn shape_summary(shape: &Option<FieldSchema>) -> String {
+    match shape {
+        Some(shape) => format!("`{}`", serde_json::to_string(shape).unwrap_or_default()),
+        None => "-".to_string(),
+    }
+}
+
+fn endpoint_table(routes: &[RouteObject]) -> String {
+    let mut table: String = String::from(
+        "## Endpoints\n\n| Method | Route | Request body | Response |\n| --- | --- | --- | --- |\n",
+    );
+
+    for route_object in routes {
+        table.push_str(&format!(
+            "| {} | `{}` | {} | {} |\n",
+            route_object.method.to_string().to_uppercase(),
+            route_object.route,
+            shape_summary(&route_object.request_body),
+            shape_summary(&route_object.response)
+        ));
+    }
+
+    table
+}
+
+// Path parameters are filled with 1 so every example can be pasted straight into a shell.
+fn curl_examples(routes: &[RouteObject]) -> String {
+    let mut examples: String = String::from("## Examples\n\n```sh\n");
+
+    for route_object in routes {
+        let route: String = route_object
+            .route
+            .split('/')
+            .map(|segment| {
+                if segment.starts_with('{') && segment.ends_with('}') {
+                    "1"
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<&str>>()
+            .join("/");
+
+        let mut example: String = format!(
+            "curl -X {} http://localhost:8080{}",
+            route_object.method.to_string().to_uppercase(),
+            route
+        );
+
+        if let Some(request_body) = &route_object.request_body {
+            example.push_str(&format!(
+                " -H 'Content-Type: application/json' -d '{}'",
+                sample_value(request_body)
+            ));
+        }
+
+        examples.push_str(&example);
+        examples.push('\n');
+    }
+
+    examples.push_str("```\n");
+    examples
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentDocumentation {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let routes: Vec<RouteObject> = match &factsheet.api_endpoint_schema {
+            Some(routes) => routes.clone(),
+            None => return Err("No API endpoint schema on the fact sheet.".into()),
+        };
+
+        while self.attributes.state != AgentState::Finishing {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    self.call_project_readme(factsheet, &routes).await;
+                    self.attributes.state = AgentState::Working;
+                }
+                AgentState::Working => {
+                    let document: Value =
+                        build_openapi_document(&factsheet.project_description, &routes);
+                    self.openapi_yaml = Some(serde_yaml::to_string(&document)?);
+                    self.attributes.state = AgentState::UnitTesting;
+                }
+                AgentState::UnitTesting => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Documentation testing: validating the OpenAPI document",
+                    );
+
+                    let openapi_yaml: &String = self
+                        .openapi_yaml
+                        .as_ref()
+                        .expect("No OpenAPI document written");
+
+                    validate_openapi_yaml(openapi_yaml)?;
+                    save_openapi_spec(openapi_yaml);
+
+                    self.attributes.state = AgentState::Finishing;
+                }
+                _ => {
+                    self.attributes.state = AgentState::Finishing;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agents::agent_traits::HttpMethod;
+    use serde_json::json;
+
+    #[test]
+    fn tests_endpoint_docs() {
+        let routes: Vec<RouteObject> = vec![RouteObject {
+            is_route_dynamic: true,
+            method: HttpMethod::Put,
+            request_body: FieldSchema::from_value(
+                &json!({ "name": "string", "completed": "bool" }),
+            ),
+            response: None,
+            route: "/item/{id}".to_string(),
+        }];
+
+        let table: String = endpoint_table(&routes);
+        assert!(table.contains("| PUT | `/item/{id}` |"));
+
+        let examples: String = curl_examples(&routes);
+        assert!(examples.contains(
+            r#"curl -X PUT http://localhost:8080/item/1 -H 'Content-Type: application/json' -d '{"completed":true,"name":"example"}'"#
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::ai_functions::aifunc_frontend::print_frontend_code;
+use crate::helpers::command_line::PrintCommand;
+use crate::helpers::docker::backend_base_url;
+use crate::helpers::general::{ai_task_request, save_frontend_code};
+use crate::models::agents::agent_traits::{FactSheet, RouteObject, SpecialFunctions};
+use crate::models::agents_basic::basic_agents::{AgentState, BasicAgent};
+
+use async_trait::async_trait;
+
+#[derive(Debug)]
+pub struct AgentFrontendDeveloper {
+    attributes: BasicAgent,
+}
+
+impl AgentFrontendDeveloper {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Develops a static frontend that calls the backend api endpoints"
+                .to_string(),
+            position: "Frontend developer".to_string(),
+            state: AgentState::Discovery,
+            memory: vec![],
+        };
+
+        Self { attributes }
+    }
+
+    async fn call_initial_frontend_code(
+        &mut self,
+        factsheet: &mut FactSheet,
+        api_endpoints: &Vec<RouteObject>,
+    ) {
+        let api_endpoints_str: String =
+            serde_json::to_string(api_endpoints).expect("Failed to serialize the API endpoints.");
+
+        let base_url: String = factsheet
+            .backend_base_url
+            .clone()
+            .unwrap_or_else(backend_base_url);
+
+        let msg_context: String = format!(
+            "PROJECT_DESCRIPTION: {} \n API_ENDPOINT_SCHEMA: {} \n BACKEND_BASE_URL: {} \n",
+            factsheet.project_description, api_endpoints_str, base_url
+        );
+
+        let ai_response: String = ai_task_request(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_frontend_code),
+            print_frontend_code,
+        )
+        .await;
+
+        save_frontend_code(&ai_response);
+        factsheet.frontend_code = Some(ai_response);
+    }
+}
+
+// Routes are matched on their static prefix so "/item/{id}" is found in "`/item/${id}`".
+fn find_uncalled_routes(frontend_code: &str, api_endpoints: &[RouteObject]) -> Vec<String> {
+    api_endpoints
+        .iter()
+        .map(|route_object| route_object.route.clone())
+        .filter(|route| {
+            let static_prefix: &str = route.split('{').next().unwrap_or(route);
+            !frontend_code.contains(static_prefix.trim_end_matches('/'))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentFrontendDeveloper {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let api_endpoints: Vec<RouteObject> = match &factsheet.api_endpoint_schema {
+            Some(api_endpoints) => api_endpoints.clone(),
+            None => return Err("No API endpoint schema on the fact sheet.".into()),
+        };
+
+        while self.attributes.state != AgentState::Finishing {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    self.call_initial_frontend_code(factsheet, &api_endpoints)
+                        .await;
+                    self.attributes.state = AgentState::UnitTesting;
+                }
+                AgentState::UnitTesting => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Frontend code unit testing: checking every route is called",
+                    );
+
+                    let frontend_code: &String = factsheet
+                        .frontend_code
+                        .as_ref()
+                        .expect("No frontend code on fact sheet");
+
+                    for route in find_uncalled_routes(frontend_code, &api_endpoints) {
+                        let err_msg: String = format!("Route is never called: {}", route);
+                        PrintCommand::Issue
+                            .print_agent_msg(self.attributes.position.as_str(), err_msg.as_str());
+                    }
+
+                    self.attributes.state = AgentState::Finishing;
+                }
+                _ => {
+                    self.attributes.state = AgentState::Finishing;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agents::agent_traits::HttpMethod;
+
+    fn route(route: &str, method: HttpMethod) -> RouteObject {
+        RouteObject {
+            is_route_dynamic: route.contains('{'),
+            method,
+            request_body: None,
+            response: None,
+            route: route.to_string(),
+        }
+    }
+
+    #[test]
+    fn tests_find_uncalled_routes() {
+        let frontend_code: &str = r#"
+            fetch(`http://localhost:8080/item/${id}`);
+            fetch("http://localhost:8080/crypto");
+        "#;
+
+        let api_endpoints: Vec<RouteObject> = vec![
+            route("/item/{id}", HttpMethod::Get),
+            route("/crypto", HttpMethod::Get),
+            route("/signup", HttpMethod::Post),
+        ];
+
+        let uncalled: Vec<String> = find_uncalled_routes(frontend_code, &api_endpoints);
+        assert_eq!(uncalled, vec!["/signup".to_string()]);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::ai_functions::aifunc_review::print_security_findings;
+use crate::helpers::code_audit::audit_generated_code;
+use crate::helpers::command_line::PrintCommand;
+use crate::helpers::dependencies::project_dependency_plan;
+use crate::helpers::file_tree::FileTree;
+use crate::helpers::general::try_ai_task_request_decoded;
+use crate::models::agents::agent_traits::{AuditFinding, FactSheet, SpecialFunctions};
+use crate::models::agents_basic::basic_agents::{AgentState, BasicAgent};
+
+use async_trait::async_trait;
+use std::cmp::Reverse;
+
+#[derive(Debug)]
+pub struct AgentCodeReviewer {
+    attributes: BasicAgent,
+    findings: Vec<AuditFinding>,
+}
+
+impl AgentCodeReviewer {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Reviews generated backend code for risky patterns before it is run"
+                .to_string(),
+            position: "Code reviewer".to_string(),
+            state: AgentState::Discovery,
+            memory: vec![],
+        };
+
+        Self {
+            attributes,
+            findings: vec![],
+        }
+    }
+
+    async fn call_security_findings(&mut self, backend_code: &str) {
+        let msg_context: String = format!("BACKEND_CODE: {}", numbered_lines(backend_code));
+
+        // A reply that doesn't decode leaves the static findings to decide on their own.
+        match try_ai_task_request_decoded::<Vec<AuditFinding>>(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_security_findings),
+            print_security_findings,
+        )
+        .await
+        {
+            Ok(ai_response) => merge_findings(&mut self.findings, ai_response),
+            Err(e) => PrintCommand::Issue.print_agent_msg(self.attributes.position.as_str(), &e),
+        }
+    }
+}
+
+// Line numbers let the LLM report findings on the same lines the static audit uses. Like the
+// audit, every file of a multi-file project is numbered from 1.
+fn numbered_lines(code: &str) -> String {
+    let number = |contents: &str| -> String {
+        contents
+            .lines()
+            .enumerate()
+            .map(|(index, line)| format!("{:>4} | {}", index + 1, line))
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    match FileTree::from_code(code) {
+        Ok(mut file_tree) => {
+            for contents in file_tree.files.values_mut() {
+                *contents = number(contents);
+            }
+            file_tree.to_code()
+        }
+        Err(_) => number(code),
+    }
+}
+
+// Findings without a line, like new dependencies, only repeat each other with the same message.
+fn is_same_finding(finding: &AuditFinding, other: &AuditFinding) -> bool {
+    finding.rule == other.rule
+        && finding.file == other.file
+        && finding.line == other.line
+        && (finding.line.is_some() || finding.message == other.message)
+}
+
+fn merge_findings(findings: &mut Vec<AuditFinding>, new_findings: Vec<AuditFinding>) {
+    for new_finding in new_findings {
+        let is_duplicate: bool = findings
+            .iter()
+            .any(|finding| is_same_finding(finding, &new_finding));
+
+        if !is_duplicate {
+            findings.push(new_finding);
+        }
+    }
+
+    findings.sort_by_key(|finding| Reverse(finding.severity));
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentCodeReviewer {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let backend_code: String = match &factsheet.backend_code {
+            Some(backend_code) => backend_code.clone(),
+            None => return Err("No backend code on the fact sheet.".into()),
+        };
+
+        self.findings = vec![];
+        self.attributes.state = AgentState::Discovery;
+
+        while self.attributes.state != AgentState::Finishing {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Code review: statically scanning the backend code",
+                    );
+
+                    let static_findings: Vec<AuditFinding> = audit_generated_code(&backend_code);
+                    merge_findings(&mut self.findings, static_findings);
+
+                    let dependency_findings: Vec<AuditFinding> =
+                        project_dependency_plan(&backend_code).audit_findings();
+                    merge_findings(&mut self.findings, dependency_findings);
+
+                    self.attributes.state = AgentState::Working;
+                }
+                AgentState::Working => {
+                    self.call_security_findings(&backend_code).await;
+                    self.attributes.state = AgentState::Finishing;
+                }
+                _ => {
+                    self.attributes.state = AgentState::Finishing;
+                }
+            }
+        }
+
+        factsheet.audit_findings = Some(self.findings.clone());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agents::agent_traits::Severity;
+
+    fn finding(severity: Severity, rule: &str, file: Option<&str>, line: usize) -> AuditFinding {
+        AuditFinding {
+            severity,
+            rule: rule.to_string(),
+            message: rule.to_string(),
+            file: file.map(str::to_string),
+            line: Some(line),
+        }
+    }
+
+    #[test]
+    fn tests_merge_findings() {
+        let mut findings: Vec<AuditFinding> = vec![
+            finding(Severity::Medium, "public_listener", None, 9),
+            finding(Severity::High, "file_deletion", Some("src/db.rs"), 4),
+        ];
+
+        merge_findings(
+            &mut findings,
+            vec![
+                finding(Severity::Medium, "public_listener", None, 9),
+                finding(Severity::Critical, "unsafe", None, 3),
+                finding(Severity::High, "file_deletion", Some("src/db.rs"), 4),
+                finding(Severity::High, "file_deletion", Some("src/handlers.rs"), 4),
+            ],
+        );
+
+        let rules: Vec<&str> = findings.iter().map(|f| f.rule.as_str()).collect();
+        assert_eq!(
+            rules,
+            vec![
+                "unsafe",
+                "file_deletion",
+                "file_deletion",
+                "public_listener"
+            ]
+        );
+    }
+
+    #[test]
+    fn tests_numbered_lines_per_file() {
+        let envelope: &str = "=== FILE: src/main.rs ===\nmod db;\nfn main() {}\n=== END FILE ===\n=== FILE: src/db.rs ===\npub fn reset() {}\n=== END FILE ===\n";
+        let numbered: String = numbered_lines(envelope);
+
+        assert!(numbered.contains("=== FILE: src/db.rs ===\n   1 | pub fn reset() {}\n"));
+        assert!(numbered.contains("   2 | fn main() {}"));
+        assert_eq!(numbered_lines("fn main() {}"), "   1 | fn main() {}");
+    }
+}
@@ -0,0 +1,304 @@
+use crate::ai_functions::aifunc_testing::print_api_tests;
+use crate::helpers::command_line::{confirm_safe_code, PrintCommand};
+use crate::helpers::diagnostics::MAIN_SOURCE_FILE;
+use crate::helpers::file_tree::FileTree;
+use crate::helpers::framework::{project_context, target_framework};
+use crate::helpers::general::{
+    ai_task_request, save_backend_code, test_backend_project, API_TESTS_FILE,
+};
+use crate::models::agents::agent_backend::{AgentBackendDeveloper, BugStage};
+use crate::models::agents::agent_reviewer::AgentCodeReviewer;
+use crate::models::agents::agent_traits::{AuditFinding, FactSheet, SpecialFunctions};
+use crate::models::agents_basic::basic_agents::{AgentState, BasicAgent};
+
+use async_trait::async_trait;
+
+const TESTS_MODULE_DECLARATION: &str = "#[cfg(test)]\nmod api_tests;";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestFailure {
+    pub name: String,
+    pub output: String,
+}
+
+#[derive(Debug)]
+pub struct AgentTestWriter {
+    attributes: BasicAgent,
+    // Failing tests are fixed through the backend developer's fix step and bug limit.
+    backend_developer: AgentBackendDeveloper,
+    api_tests: Option<String>,
+}
+
+impl AgentTestWriter {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Writes and runs integration tests for the backend api endpoints"
+                .to_string(),
+            position: "Test writer".to_string(),
+            state: AgentState::Discovery,
+            memory: vec![],
+        };
+
+        Self {
+            attributes,
+            backend_developer: AgentBackendDeveloper::new(),
+            api_tests: None,
+        }
+    }
+
+    async fn call_api_tests(&mut self, factsheet: &mut FactSheet) {
+        let mut msg_context: String = format!(
+            "BACKEND_CODE: {:?} \n API_ENDPOINT_SCHEMA: {:?} \n {} TEST_CONVENTIONS: {} \n",
+            factsheet.backend_code,
+            factsheet.api_endpoint_schema,
+            project_context(),
+            target_framework().test_conventions()
+        );
+
+        if let Some(bug_errors) = self.backend_developer.bug_errors() {
+            msg_context.push_str(&format!("PREVIOUS_TEST_FAILURES: {} \n", bug_errors));
+        }
+
+        let ai_response: String = ai_task_request(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_api_tests),
+            print_api_tests,
+        )
+        .await;
+
+        self.api_tests = Some(ai_response);
+    }
+}
+
+// The tests become part of the backend's file tree, so they are reviewed like the rest of the
+// code before cargo test runs them. The declaration goes into src/main.rs itself, also when the
+// backend is a multi-file envelope.
+fn attach_api_tests(backend_code: &str, api_tests: &str) -> String {
+    let mut file_tree: FileTree =
+        FileTree::from_code(backend_code).unwrap_or_else(|_| FileTree::single(backend_code));
+
+    file_tree
+        .files
+        .insert(API_TESTS_FILE.to_string(), api_tests.to_string());
+
+    if let Some(main) = file_tree.files.get_mut(MAIN_SOURCE_FILE) {
+        if !main.contains("mod api_tests;") {
+            *main = format!("{}\n\n{}\n", main.trim_end(), TESTS_MODULE_DECLARATION);
+        }
+    }
+
+    file_tree.to_code()
+}
+
+// Reads the libtest output of `cargo test`, pairing each failed test with its captured stdout.
+fn parse_test_failures(test_output: &str) -> Vec<TestFailure> {
+    let mut failures: Vec<TestFailure> = test_output
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("test ")
+                .and_then(|rest| rest.strip_suffix(" ... FAILED"))
+        })
+        .map(|name| TestFailure {
+            name: name.to_string(),
+            output: String::new(),
+        })
+        .collect();
+
+    let mut current: Option<usize> = None;
+
+    for line in test_output.lines() {
+        if let Some(section) = line
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        {
+            current = failures.iter().position(|failure| failure.name == section);
+            continue;
+        }
+
+        if line == "failures:" {
+            current = None;
+        }
+
+        if let Some(index) = current {
+            failures[index].output.push_str(line);
+            failures[index].output.push('\n');
+        }
+    }
+
+    failures
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentTestWriter {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if factsheet.backend_code.is_none() || factsheet.api_endpoint_schema.is_none() {
+            return Err("No backend code or API endpoint schema on the fact sheet.".into());
+        }
+
+        if let Some(backend_code) = &factsheet.backend_code {
+            self.backend_developer.set_tested_code(backend_code);
+        }
+
+        while self.attributes.state != AgentState::Finishing {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    self.call_api_tests(factsheet).await;
+                    self.attributes.state = AgentState::Working;
+                }
+                AgentState::Working => {
+                    // A failure can be in the backend or in the tests, so both are rewritten.
+                    if self.backend_developer.bug_errors().is_some() {
+                        self.backend_developer.call_fix_code_bugs(factsheet).await;
+                        self.call_api_tests(factsheet).await;
+                    }
+
+                    let backend_code: String = attach_api_tests(
+                        factsheet
+                            .backend_code
+                            .as_ref()
+                            .expect("No backend code on fact sheet"),
+                        self.api_tests.as_deref().unwrap_or_default(),
+                    );
+
+                    save_backend_code(&backend_code, &self.attributes.position, "add api tests");
+                    factsheet.backend_code = Some(backend_code);
+
+                    self.attributes.state = AgentState::UnitTesting;
+                }
+                AgentState::UnitTesting => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Backend integration testing: ensuring safe code",
+                    );
+
+                    AgentCodeReviewer::new().execute(factsheet).await?;
+                    let findings: &[AuditFinding] =
+                        factsheet.audit_findings.as_deref().unwrap_or_default();
+
+                    if !confirm_safe_code(findings) {
+                        return Err("Stopped before running the integration tests.".into());
+                    }
+
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Backend integration testing: running cargo test",
+                    );
+
+                    let test_backend_server: std::process::Output = test_backend_project();
+
+                    if test_backend_server.status.success() {
+                        PrintCommand::UnitTest.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "Backend integration testing is completed.",
+                        );
+
+                        self.attributes.state = AgentState::Finishing;
+                        continue;
+                    }
+
+                    let test_output: String =
+                        String::from_utf8_lossy(&test_backend_server.stdout).to_string();
+                    let failures: Vec<TestFailure> = parse_test_failures(&test_output);
+
+                    // No failed tests means the tests did not compile, so the errors are on stderr.
+                    let error_str: String = if failures.is_empty() {
+                        String::from_utf8_lossy(&test_backend_server.stderr).to_string()
+                    } else {
+                        failures
+                            .iter()
+                            .map(|failure| format!("{}:\n{}", failure.name, failure.output))
+                            .collect::<Vec<String>>()
+                            .join("\n")
+                    };
+
+                    for failure in &failures {
+                        let err_msg: String = format!("Test failed: {}", failure.name);
+                        PrintCommand::Issue
+                            .print_agent_msg(self.attributes.position.as_str(), err_msg.as_str());
+                    }
+
+                    self.backend_developer
+                        .record_bugs(factsheet, BugStage::Tests, error_str)?;
+                    self.attributes.state = AgentState::Working;
+                }
+                _ => {
+                    self.attributes.state = AgentState::Finishing;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_parse_test_failures() {
+        let test_output: &str = "
+running 2 tests
+test api_tests::test_read_item ... FAILED
+test api_tests::test_create_item ... ok
+
+failures:
+
+---- api_tests::test_read_item stdout ----
+thread 'api_tests::test_read_item' panicked at src/api_tests.rs:20:9:
+assertion failed: resp.status().is_success()
+
+failures:
+    api_tests::test_read_item
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
+";
+
+        let failures: Vec<TestFailure> = parse_test_failures(test_output);
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].name, "api_tests::test_read_item");
+        assert!(failures[0]
+            .output
+            .contains("assertion failed: resp.status().is_success()"));
+    }
+
+    #[test]
+    fn tests_attach_api_tests() {
+        let api_tests: &str = "#[test]\nfn test_read_item() {}\n";
+        let backend_code: String = attach_api_tests("fn main() {}\n", api_tests);
+        let attached: FileTree = FileTree::from_code(&backend_code).unwrap();
+
+        assert_eq!(
+            attached.files[MAIN_SOURCE_FILE],
+            "fn main() {}\n\n#[cfg(test)]\nmod api_tests;\n"
+        );
+        assert_eq!(attached.files[API_TESTS_FILE], api_tests);
+
+        let rewritten: FileTree =
+            FileTree::from_code(&attach_api_tests(&backend_code, "// rewritten\n")).unwrap();
+        assert_eq!(
+            rewritten.files[MAIN_SOURCE_FILE],
+            attached.files[MAIN_SOURCE_FILE]
+        );
+        assert_eq!(rewritten.files[API_TESTS_FILE], "// rewritten\n");
+
+        let envelope: &str = "=== FILE: src/main.rs ===\nmod db;\n\nfn main() {}\n=== END FILE ===\n\n=== FILE: src/db.rs ===\npub fn load() {}\n=== END FILE ===\n";
+        let declared: FileTree =
+            FileTree::from_code(&attach_api_tests(envelope, api_tests)).unwrap();
+
+        assert_eq!(
+            declared.files[MAIN_SOURCE_FILE],
+            "mod db;\n\nfn main() {}\n\n#[cfg(test)]\nmod api_tests;\n"
+        );
+        assert_eq!(declared.files["src/db.rs"], "pub fn load() {}\n");
+    }
+}
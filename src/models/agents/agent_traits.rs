@@ -1,14 +1,133 @@
 use crate::models::agents_basic::basic_agents::BasicAgent;
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+    Options,
+}
+
+impl Serialize for HttpMethod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for HttpMethod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let method: String = String::deserialize(deserializer)?;
+        HttpMethod::from_str(method.trim())
+            .map_err(|_| de::Error::custom(format!("unknown http method: {}", method)))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum FieldType {
+    Number,
+    Float,
+    String,
+    Bool,
+    List,
+    Any,
+}
+
+impl FieldType {
+    pub fn from_type_name(type_name: &str) -> Self {
+        match type_name.trim().to_lowercase().as_str() {
+            "number" | "integer" | "int" | "u64" | "i64" | "u32" | "i32" | "usize" => Self::Number,
+            "float" | "f64" | "f32" | "decimal" => Self::Float,
+            "string" | "str" | "text" => Self::String,
+            "bool" | "boolean" => Self::Bool,
+            "list" | "array" | "vec" => Self::List,
+            _ => Self::Any,
+        }
+    }
+}
+
+// Serializes back to the same shape the LLM prints: "number", [ ... ] or { "key": ... }.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum FieldSchema {
+    Field(FieldType),
+    List(Vec<FieldSchema>),
+    Object(BTreeMap<String, FieldSchema>),
+    // An Option<T> in the code: null or a missing key is fine. Serializes as the inner shape.
+    Optional(Box<FieldSchema>),
+}
+
+impl FieldSchema {
+    // "None", "not_provided" and null are how the LLM marks a missing body.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => None,
+            Value::String(type_name) => match type_name.trim().to_lowercase().as_str() {
+                "" | "none" | "null" | "not_provided" => None,
+                _ => Some(Self::Field(FieldType::from_type_name(type_name))),
+            },
+            Value::Bool(_) => Some(Self::Field(FieldType::Bool)),
+            Value::Number(number) if number.is_f64() => Some(Self::Field(FieldType::Float)),
+            Value::Number(_) => Some(Self::Field(FieldType::Number)),
+            Value::Array(items) => Some(Self::List(
+                items.iter().filter_map(Self::from_value).collect(),
+            )),
+            Value::Object(fields) => Some(Self::Object(
+                fields
+                    .iter()
+                    .map(|(name, field)| {
+                        let schema: FieldSchema =
+                            Self::from_value(field).unwrap_or(Self::Field(FieldType::Any));
+                        (name.clone(), schema)
+                    })
+                    .collect(),
+            )),
+        }
+    }
+}
+
+fn deserialize_lenient_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Bool(flag) => Ok(flag),
+        Value::String(flag) => match flag.trim().to_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok(true),
+            "false" | "no" | "0" | "" => Ok(false),
+            _ => Err(de::Error::custom(format!("expected a bool, got {}", flag))),
+        },
+        Value::Number(number) => Ok(number.as_f64() != Some(0.0)),
+        other => Err(de::Error::custom(format!("expected a bool, got {}", other))),
+    }
+}
+
+fn deserialize_field_schema<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<FieldSchema>, D::Error> {
+    let value: Value = Value::deserialize(deserializer)?;
+    Ok(FieldSchema::from_value(&value))
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RouteObject {
-    pub is_route_dynamic: String,
-    pub method: String,
-    pub request_body: serde_json::Value,
-    pub response: serde_json::Value,
+    #[serde(deserialize_with = "deserialize_lenient_bool")]
+    pub is_route_dynamic: bool,
+    pub method: HttpMethod,
+    #[serde(default, deserialize_with = "deserialize_field_schema")]
+    pub request_body: Option<FieldSchema>,
+    #[serde(default, deserialize_with = "deserialize_field_schema")]
+    pub response: Option<FieldSchema>,
     pub route: String,
 }
 
@@ -19,13 +138,113 @@ pub struct ProjectScope {
     pub is_external_urls_required: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityField {
+    pub name: String,
+    pub field_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityModel {
+    pub name: String,
+    pub fields: Vec<EntityField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataModel {
+    pub entities: Vec<EntityModel>,
+    pub database_code: Option<String>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+// The LLM writes "High" as often as "high", so severities are read like http methods.
+impl<'de> Deserialize<'de> for Severity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let severity: String = String::deserialize(deserializer)?;
+        Severity::from_str(severity.trim())
+            .map_err(|_| de::Error::custom(format!("unknown severity: {}", severity)))
+    }
+}
+
+fn deserialize_lenient_line<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<usize>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(number) => Ok(number.as_u64().map(|line| line as usize)),
+        Value::String(line) => Ok(line.trim().parse().ok()),
+        _ => Ok(None),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditFinding {
+    pub severity: Severity,
+    pub rule: String,
+    pub message: String,
+    // Source file of a multi-file project. Lines are counted from the start of that file.
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient_line")]
+    pub line: Option<usize>,
+}
+
+// Which known good version the backend was restored to after the agent gave up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum RestoredVersion {
+    // Compiled and passed the endpoint tests.
+    Tested,
+    // Compiled only.
+    Built,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackendFailure {
+    pub reason: String,
+    pub attempts: u8,
+    pub last_errors: Option<String>,
+    pub restored: Option<RestoredVersion>,
+}
+
+impl BackendFailure {
+    pub fn describe(&self) -> String {
+        let restored: String = match self.restored {
+            Some(version) => format!("restored the last {} version", version),
+            None => "no working version to restore".to_string(),
+        };
+
+        format!(
+            "{} after {} attempts, {}",
+            self.reason, self.attempts, restored
+        )
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct FactSheet {
     pub project_description: String,
     pub project_scope: Option<ProjectScope>,
     pub external_urls: Option<Vec<String>>,
+    pub data_model: Option<DataModel>,
     pub backend_code: Option<String>,
+    pub audit_findings: Option<Vec<AuditFinding>>,
     pub api_endpoint_schema: Option<Vec<RouteObject>>,
+    pub frontend_code: Option<String>,
+    pub backend_failure: Option<BackendFailure>,
+    // Lint warnings left in the last backend version that passed, one line per lint.
+    pub remaining_warnings: Option<Vec<String>>,
+    // Where the deployed backend is reached, e.g. "http://localhost:8080". The frontend calls it.
+    pub backend_base_url: Option<String>,
 }
 
 #[async_trait]
@@ -36,3 +255,78 @@ pub trait SpecialFunctions: Debug {
         factsheet: &mut FactSheet,
     ) -> Result<(), Box<dyn std::error::Error>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_audit_findings_from_llm_output() {
+        let findings_str: &str = r#"
+        [
+            { "severity": "High", "rule": "sql", "message": "query built from input", "line": "12" },
+            { "severity": "info ", "rule": "x", "message": "x" },
+            { "severity": "LOW", "rule": "log", "message": "logs the password", "line": null }
+        ]
+        "#;
+
+        assert!(serde_json::from_str::<Vec<AuditFinding>>(findings_str).is_err());
+
+        let findings_str: String = findings_str.replace("info ", "medium");
+        let findings: Vec<AuditFinding> = serde_json::from_str(&findings_str).unwrap();
+
+        assert_eq!(findings[0].severity, Severity::High);
+        assert_eq!(findings[0].line, Some(12));
+        assert_eq!(findings[1].severity, Severity::Medium);
+        assert_eq!(findings[1].line, None);
+        assert_eq!(findings[2].severity, Severity::Low);
+    }
+
+    #[test]
+    fn tests_route_object_from_llm_output() {
+        let api_endpoints_str: &str = r#"
+        [
+            {
+                "route": "/item/{id}",
+                "is_route_dynamic": "true",
+                "method": "GET",
+                "request_body": "None",
+                "response": { "id": "number", "name": "string", "tags": ["string"] }
+            },
+            {
+                "route": "/crypto",
+                "is_route_dynamic": false,
+                "method": "post",
+                "request_body": { "symbol": "string", "price": "float" },
+                "response": "not_provided"
+            }
+        ]
+        "#;
+
+        let routes: Vec<RouteObject> = serde_json::from_str(api_endpoints_str).unwrap();
+
+        assert!(routes[0].is_route_dynamic);
+        assert_eq!(routes[0].method, HttpMethod::Get);
+        assert_eq!(routes[0].request_body, None);
+
+        let mut expected_response: BTreeMap<String, FieldSchema> = BTreeMap::new();
+        expected_response.insert("id".to_string(), FieldSchema::Field(FieldType::Number));
+        expected_response.insert("name".to_string(), FieldSchema::Field(FieldType::String));
+        expected_response.insert(
+            "tags".to_string(),
+            FieldSchema::List(vec![FieldSchema::Field(FieldType::String)]),
+        );
+        assert_eq!(
+            routes[0].response,
+            Some(FieldSchema::Object(expected_response))
+        );
+
+        assert!(!routes[1].is_route_dynamic);
+        assert_eq!(routes[1].method, HttpMethod::Post);
+        assert_eq!(routes[1].response, None);
+
+        let round_trip: Vec<RouteObject> =
+            serde_json::from_str(&serde_json::to_string(&routes).unwrap()).unwrap();
+        assert_eq!(round_trip, routes);
+    }
+}
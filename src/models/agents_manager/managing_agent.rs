@@ -1,9 +1,23 @@
-use crate::models::agents::agent_traits::{FactSheet, SpecialFunctions};
+use crate::models::agents::agent_traits::{BackendFailure, FactSheet, SpecialFunctions};
 use crate::models::agents_basic::basic_agents::{AgentState, BasicAgent};
 
 use crate::ai_functions::aifunc_managing::convert_user_input_to_goal;
-use crate::helpers::general::ai_task_request;
+use crate::helpers::command_line::PrintCommand;
+use crate::helpers::docker::backend_base_url;
+use crate::helpers::general::{
+    ai_task_request, save_factsheet, save_run_report, set_run_workspace,
+};
+use crate::helpers::sandbox::selected_sandbox;
+use crate::helpers::templates::selected_template;
+use crate::helpers::workspace::{start_run, RunWorkspace};
 use crate::models::agents::agent_architect::AgentSolutionArchitect;
+use crate::models::agents::agent_auth::AgentAuthDeveloper;
+use crate::models::agents::agent_backend::AgentBackendDeveloper;
+use crate::models::agents::agent_database::AgentDatabaseDesigner;
+use crate::models::agents::agent_devops::AgentDevOps;
+use crate::models::agents::agent_docs::AgentDocumentation;
+use crate::models::agents::agent_frontend::AgentFrontendDeveloper;
+use crate::models::agents::agent_tester::AgentTestWriter;
 use crate::models::general::llm::Message;
 
 #[derive(Debug)]
@@ -24,6 +38,19 @@ impl ManagingAgent {
             memory: vec![],
         };
 
+        selected_sandbox()?.check()?;
+
+        let run_workspace: RunWorkspace = start_run(&usr_req)?;
+        let run_root: String = run_workspace.root.display().to_string();
+        set_run_workspace(run_workspace);
+
+        let run_msg: String = format!(
+            "Started run {} from template {}",
+            run_root,
+            selected_template()
+        );
+        PrintCommand::UnitTest.print_agent_msg(&position, run_msg.as_str());
+
         let project_description: String = ai_task_request(
             usr_req,
             &position,
@@ -38,8 +65,14 @@ impl ManagingAgent {
             project_description,
             project_scope: None,
             external_urls: None,
+            data_model: None,
             backend_code: None,
+            audit_findings: None,
             api_endpoint_schema: None,
+            frontend_code: None,
+            backend_failure: None,
+            remaining_warnings: None,
+            backend_base_url: Some(backend_base_url()),
         };
 
         Ok(Self {
@@ -55,24 +88,131 @@ impl ManagingAgent {
 
     fn create_agents(&mut self) {
         self.add_agent(Box::new(AgentSolutionArchitect::new()));
+        self.add_agent(Box::new(AgentDatabaseDesigner::new()));
+        self.add_agent(Box::new(AgentBackendDeveloper::new()));
+        self.add_agent(Box::new(AgentAuthDeveloper::new()));
+        self.add_agent(Box::new(AgentTestWriter::new()));
+        self.add_agent(Box::new(AgentFrontendDeveloper::new()));
+        self.add_agent(Box::new(AgentDocumentation::new()));
+        self.add_agent(Box::new(AgentDevOps::new()));
     }
 
     pub async fn execute_project(&mut self) {
         self.create_agents();
 
+        let mut results: Vec<(String, Result<(), String>)> = vec![];
+
         for agent in &mut self.agents {
             let agent_res: Result<(), Box<dyn std::error::Error>> =
                 agent.execute(&mut self.factsheet).await;
 
             let agent_info: &BasicAgent = agent.get_attributes_from_agent();
             dbg!(agent_info);
+
+            results.push((
+                agent_info.position.clone(),
+                agent_res.map_err(|e| e.to_string()),
+            ));
+
+            let factsheet_str: String = serde_json::to_string_pretty(&self.factsheet)
+                .expect("Failed to serialize the factsheet.");
+            save_factsheet(&factsheet_str);
+        }
+
+        save_run_report(&format_run_report(
+            &self.factsheet.project_description,
+            &results,
+            self.factsheet.backend_failure.as_ref(),
+            self.factsheet.remaining_warnings.as_deref(),
+        ));
+    }
+}
+
+fn format_run_report(
+    project_description: &str,
+    results: &[(String, Result<(), String>)],
+    backend_failure: Option<&BackendFailure>,
+    remaining_warnings: Option<&[String]>,
+) -> String {
+    let mut report: String = format!(
+        "# Run report\n\n{}\n\n| Agent | Result |\n| --- | --- |\n",
+        project_description.trim()
+    );
+
+    for (position, result) in results {
+        let outcome: String = match result {
+            Ok(()) => "Completed".to_string(),
+            Err(e) => format!("Failed: {}", e.replace('\n', " ").replace('|', "\\|")),
+        };
+
+        report.push_str(&format!("| {} | {} |\n", position, outcome));
+    }
+
+    if let Some(failure) = backend_failure {
+        report.push_str(&format!("\n## Backend failure\n\n{}\n", failure.describe()));
+
+        if let Some(last_errors) = &failure.last_errors {
+            report.push_str(&format!("\n```\n{}\n```\n", last_errors.trim()));
+        }
+    }
+
+    if let Some(remaining_warnings) = remaining_warnings.filter(|warnings| !warnings.is_empty()) {
+        report.push_str("\n## Remaining warnings\n\n");
+
+        for warning_line in remaining_warnings {
+            report.push_str(&format!("- {}\n", warning_line));
         }
     }
+
+    report
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::agents::agent_traits::RestoredVersion;
+
+    #[test]
+    fn tests_format_run_report() {
+        let results: Vec<(String, Result<(), String>)> = vec![
+            ("Backend developer".to_string(), Ok(())),
+            (
+                "Authentication developer".to_string(),
+                Err("Too many bugs\nfound | stopped".to_string()),
+            ),
+        ];
+
+        let report: String = format_run_report("A todo list API", &results, None, Some(&[]));
+
+        assert!(report.starts_with("# Run report\n\nA todo list API\n"));
+        assert!(report.contains("| Backend developer | Completed |\n"));
+        assert!(report
+            .contains("| Authentication developer | Failed: Too many bugs found \\| stopped |\n"));
+        assert!(!report.contains("## Backend failure"));
+        assert!(!report.contains("## Remaining warnings"));
+
+        let failure: BackendFailure = BackendFailure {
+            reason: "Too many bugs found in the backend code".to_string(),
+            attempts: 3,
+            last_errors: Some("error[E0425]: cannot find value `db`".to_string()),
+            restored: Some(RestoredVersion::Built),
+        };
+
+        let warnings: Vec<String> = vec![
+            "3 x clippy::needless_return".to_string(),
+            "1 x unused_variables".to_string(),
+        ];
+        let report: String =
+            format_run_report("A todo list API", &results, Some(&failure), Some(&warnings));
+
+        assert!(report.contains(
+            "## Backend failure\n\nToo many bugs found in the backend code after 3 attempts, restored the last built version\n"
+        ));
+        assert!(report.contains("```\nerror[E0425]: cannot find value `db`\n```\n"));
+        assert!(report.ends_with(
+            "## Remaining warnings\n\n- 3 x clippy::needless_return\n- 1 x unused_variables\n"
+        ));
+    }
 
     #[tokio::test]
     async fn tests_manager() {
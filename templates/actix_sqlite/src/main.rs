@@ -0,0 +1,113 @@
+use actix_web::{web, App, HttpResponse, HttpServer, Responder};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+const DATABASE_PATH: &str = "database.sqlite";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Task {
+    id: u64,
+    name: String,
+    completed: bool,
+}
+
+struct AppState {
+    db: Mutex<Connection>,
+}
+
+fn open_database() -> rusqlite::Result<Connection> {
+    let connection: Connection = Connection::open(DATABASE_PATH)?;
+    connection.execute(
+        "CREATE TABLE IF NOT EXISTS tasks (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            completed INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(connection)
+}
+
+fn row_to_task(row: &rusqlite::Row) -> rusqlite::Result<Task> {
+    Ok(Task {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        completed: row.get(2)?,
+    })
+}
+
+async fn create_task(app_state: web::Data<AppState>, task: web::Json<Task>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    match db.execute(
+        "INSERT OR REPLACE INTO tasks (id, name, completed) VALUES (?1, ?2, ?3)",
+        params![task.id, task.name, task.completed],
+    ) {
+        Ok(_) => HttpResponse::Ok().json(task.into_inner()),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+async fn read_task(app_state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    let task: rusqlite::Result<Option<Task>> = db
+        .query_row(
+            "SELECT id, name, completed FROM tasks WHERE id = ?1",
+            params![id.into_inner()],
+            row_to_task,
+        )
+        .optional();
+
+    match task {
+        Ok(Some(task)) => HttpResponse::Ok().json(task),
+        Ok(None) => HttpResponse::NotFound().finish(),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+async fn read_all_tasks(app_state: web::Data<AppState>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    let tasks: rusqlite::Result<Vec<Task>> = db
+        .prepare("SELECT id, name, completed FROM tasks")
+        .and_then(|mut statement| statement.query_map([], row_to_task)?.collect());
+
+    match tasks {
+        Ok(tasks) => HttpResponse::Ok().json(tasks),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+async fn delete_task(app_state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
+    let db = app_state.db.lock().unwrap();
+    match db.execute("DELETE FROM tasks WHERE id = ?1", params![id.into_inner()]) {
+        Ok(0) => HttpResponse::NotFound().finish(),
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    let connection: Connection = open_database().expect("Failed to open the database.");
+    let data: web::Data<AppState> = web::Data::new(AppState {
+        db: Mutex::new(connection),
+    });
+
+    HttpServer::new(move || {
+        App::new()
+            .app_data(data.clone())
+            .route("/task", web::post().to(create_task))
+            .route("/task", web::get().to(read_all_tasks))
+            .route("/task/{id}", web::get().to(read_task))
+            .route("/task/{id}", web::delete().to(delete_task))
+    })
+    .bind((
+        std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
+        std::env::var("PORT")
+            .ok()
+            .and_then(|port| port.parse::<u16>().ok())
+            .unwrap_or(8080),
+    ))?
+    .run()
+    .await
+}
@@ -0,0 +1,67 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Task {
+    id: u64,
+    name: String,
+    completed: bool,
+}
+
+type AppState = Arc<Mutex<HashMap<u64, Task>>>;
+
+async fn create_task(State(state): State<AppState>, Json(task): Json<Task>) -> Json<Task> {
+    state.lock().unwrap().insert(task.id, task.clone());
+    Json(task)
+}
+
+async fn read_all_tasks(State(state): State<AppState>) -> Json<Vec<Task>> {
+    Json(state.lock().unwrap().values().cloned().collect())
+}
+
+async fn read_task(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<Task>, StatusCode> {
+    state
+        .lock()
+        .unwrap()
+        .get(&id)
+        .cloned()
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn delete_task(State(state): State<AppState>, Path(id): Path<u64>) -> StatusCode {
+    match state.lock().unwrap().remove(&id) {
+        Some(_) => StatusCode::OK,
+        None => StatusCode::NOT_FOUND,
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let state: AppState = Arc::new(Mutex::new(HashMap::new()));
+
+    let app: Router = Router::new()
+        .route("/task", get(read_all_tasks).post(create_task))
+        .route("/task/:id", get(read_task).delete(delete_task))
+        .with_state(state);
+
+    let host: String = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port: u16 = std::env::var("PORT")
+        .ok()
+        .and_then(|port| port.parse::<u16>().ok())
+        .unwrap_or(8080);
+
+    let listener = tokio::net::TcpListener::bind((host, port))
+        .await
+        .expect("Failed to bind the port.");
+
+    axum::serve(listener, app).await.unwrap();
+}